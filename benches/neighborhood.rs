@@ -0,0 +1,65 @@
+//! Compares `Neighborhood::moore`, which returns a fixed-size array, with the `HashMap` it used
+//! to build on every call. Run with `cargo bench --bench neighborhood`.
+use gailibrary::engine::automaton::{Board, Neighborhood};
+use gailibrary::examples::game_of_life::GameOfLife;
+use gailibrary::fields::{Dir2D, Pos2D, DIRS8};
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 200;
+const ROUNDS: usize = 20;
+
+fn moore_map<'a>(board: &'a Board<GameOfLife>, pos: &Pos2D) -> HashMap<Dir2D, &'a GameOfLife> {
+    let mut map = HashMap::with_capacity(8);
+    DIRS8.iter().for_each(|&dir| {
+        map.insert(dir, board.find_cell(&pos.move_by_one(dir)));
+    });
+    map
+}
+
+fn measure(name: &str, positions: &[Pos2D], f: impl Fn(&Pos2D) -> usize) -> Duration {
+    let start = Instant::now();
+    let mut alive = 0;
+    for _ in 0..ROUNDS {
+        for pos in positions {
+            alive += f(black_box(pos));
+        }
+    }
+    let elapsed = start.elapsed();
+    black_box(alive);
+
+    let calls = (ROUNDS * positions.len()) as u32;
+    println!(
+        "{:>10}: {:?} total, {:?} per call",
+        name,
+        elapsed,
+        elapsed / calls
+    );
+    elapsed
+}
+
+fn main() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let soup: Vec<GameOfLife> = Pos2D::from_dims(SIZE, SIZE)
+        .iter()
+        .filter(|_| rng.gen::<bool>())
+        .map(GameOfLife::new_life)
+        .collect();
+    let board = Board::<GameOfLife>::new(SIZE, SIZE).copy_and_update(&soup);
+    let positions = Pos2D::from_dims(SIZE, SIZE);
+
+    let map = measure("HashMap", &positions, |pos| {
+        moore_map(&board, pos).values().filter(|c| c.life).count()
+    });
+    let array = measure("Neighbors", &positions, |pos| {
+        board.moore(pos).values().filter(|c| c.life).count()
+    });
+    println!(
+        "Neighbors is {:.1}x faster",
+        map.as_secs_f64() / array.as_secs_f64()
+    );
+}
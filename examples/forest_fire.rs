@@ -0,0 +1,29 @@
+//! The forest-fire model, with the seed given as the first argument (0 by default).
+//! The same seed always gives the same forest.
+extern crate ggez;
+
+use ggez::{conf, event, Context};
+
+use gailibrary::engine::automaton::Automaton;
+use gailibrary::examples::forest_fire::ForestFire;
+use gailibrary::visualisation::*;
+
+use std::env;
+
+pub fn main() {
+    let window_width: usize = 800;
+    let window_height: usize = 600;
+    let width = 200;
+    let height = 150;
+
+    let seed: u64 = env::args().nth(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let c = conf::Conf::new();
+    let ctx = &mut Context::load_from_conf("forest_fire", "makingthematrix", c).unwrap();
+    setup(ctx, window_width, window_height);
+
+    let mut auto = Automaton::<ForestFire>::new(width, height);
+    auto.set_seed(seed);
+    let state = &mut MainState::with_automaton(window_width, window_height, auto).unwrap();
+    event::run(ctx, state).unwrap();
+}
@@ -1,21 +1,52 @@
-//! The simplest possible example that does something.
+//! The simplest possible example that does something. Optionally, the path to a pattern in
+//! the RLE format can be given as the first argument, e.g.
+//! `cargo run --example game_of_life -- gosper_glider_gun.rle`.
 extern crate ggez;
 
 use ggez::{conf, event, Context};
 
+use gailibrary::engine::automaton::{Automaton, Board};
 use gailibrary::examples::game_of_life::GameOfLife;
 use gailibrary::fields::Pos2D;
+use gailibrary::patterns::rle;
 use gailibrary::visualisation::*;
 
+use std::env;
+use std::fs;
+use std::process;
+
 pub fn main() {
-    let window_size: usize = 800;
-    let dim = 100;
+    let window_width: usize = 800;
+    let window_height: usize = 600;
+    let width = 100;
+    let height = 75;
 
     let c = conf::Conf::new();
     let ctx = &mut Context::load_from_conf("game_of_life", "makingthematrix", c).unwrap();
-    setup(ctx, window_size);
+    setup(ctx, window_width, window_height);
 
-    let state = &mut MainState::<GameOfLife>::new(window_size, dim).unwrap();
-    state.add(&Pos2D::new((dim as i64) / 2, (dim as i64) / 2));
-    event::run(ctx, state).unwrap();
+    let state = match env::args().nth(1) {
+        Some(path) => {
+            let pattern = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|s| rle::read(&s).map_err(|err| err.to_string()))
+                .unwrap_or_else(|err| {
+                    eprintln!("Can't read the pattern from \"{}\": {}", path, err);
+                    process::exit(1);
+                });
+            let at = Pos2D::new(
+                (width - pattern.width().min(width)) as i64 / 2,
+                (height - pattern.height().min(height)) as i64 / 2,
+            );
+            let board = pattern.place(&Board::new(width, height), &at, GameOfLife::new_life);
+            MainState::with_automaton(window_width, window_height, Automaton::from_board(board))
+        }
+        None => MainState::<GameOfLife>::new(window_width, window_height, width, height).map(
+            |mut state| {
+                state.add(&Pos2D::new((width as i64) / 2, (height as i64) / 2));
+                state
+            },
+        ),
+    };
+    event::run(ctx, &mut state.unwrap()).unwrap();
 }
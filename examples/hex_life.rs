@@ -0,0 +1,26 @@
+//! Game of Life on a hex grid.
+extern crate ggez;
+
+use ggez::{conf, event, Context};
+
+use gailibrary::examples::hex_life::HexLife;
+use gailibrary::fields::Pos2D;
+use gailibrary::visualisation::*;
+
+pub fn main() {
+    let window_width: usize = 800;
+    let window_height: usize = 600;
+    let width = 80;
+    let height = 80;
+
+    let c = conf::Conf::new();
+    let ctx = &mut Context::load_from_conf("hex_life", "makingthematrix", c).unwrap();
+    setup(ctx, window_width, window_height);
+
+    let state = &mut MainState::<HexLife>::new(window_width, window_height, width, height).unwrap();
+    let (x, y) = ((width as i64) / 2, (height as i64) / 2);
+    state.add(&Pos2D::new(x, y));
+    state.add(&Pos2D::new(x + 1, y));
+    state.add(&Pos2D::new(x, y + 1));
+    event::run(ctx, state).unwrap();
+}
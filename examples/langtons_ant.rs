@@ -8,14 +8,17 @@ use gailibrary::fields::Pos2D;
 use gailibrary::visualisation::*;
 
 pub fn main() {
-    let window_size: usize = 800;
-    let dim = 100;
+    let window_width: usize = 800;
+    let window_height: usize = 600;
+    let width = 100;
+    let height = 75;
 
     let c = conf::Conf::new();
     let ctx = &mut Context::load_from_conf("langtons_ant", "makingthematrix", c).unwrap();
-    setup(ctx, window_size);
+    setup(ctx, window_width, window_height);
 
-    let state = &mut MainState::<LangtonsAnt>::new(window_size, dim).unwrap();
-    state.add(&Pos2D::new((dim as i64) / 2, (dim as i64) / 2));
+    let state =
+        &mut MainState::<LangtonsAnt>::new(window_width, window_height, width, height).unwrap();
+    state.add(&Pos2D::new((width as i64) / 2, (height as i64) / 2));
     event::run(ctx, state).unwrap();
 }
@@ -0,0 +1,36 @@
+//! Bugs, a Larger-than-Life rule, starting from a random soup.
+extern crate ggez;
+
+use ggez::{conf, event, Context};
+
+use gailibrary::engine::automaton::{Automaton, Board};
+use gailibrary::engine::boundary::Boundary;
+use gailibrary::engine::life_rule::LtlRule;
+use gailibrary::examples::larger_than_life::LargerThanLife;
+use gailibrary::visualisation::*;
+
+use rand::Rng;
+
+pub fn main() {
+    let window_width: usize = 800;
+    let window_height: usize = 600;
+    let width = 100;
+    let height = 75;
+
+    let c = conf::Conf::new();
+    let ctx = &mut Context::load_from_conf("larger_than_life", "makingthematrix", c).unwrap();
+    setup(ctx, window_width, window_height);
+
+    let mut rng = rand::thread_rng();
+    let board = Board::from_fn(width, height, Boundary::Torus, |pos| {
+        if rng.gen::<bool>() {
+            LargerThanLife::new_life(pos, LtlRule::BUGS)
+        } else {
+            LargerThanLife::dead(pos, LtlRule::BUGS)
+        }
+    });
+    let state =
+        &mut MainState::with_automaton(window_width, window_height, Automaton::from_board(board))
+            .unwrap();
+    event::run(ctx, state).unwrap();
+}
@@ -0,0 +1,45 @@
+//! Any Life-like rule, given in the B/S notation as the first argument, e.g.
+//! `cargo run --example life_like -- B36/S23`. Click to add living cells.
+extern crate ggez;
+
+use ggez::{conf, event, Context};
+
+use gailibrary::engine::automaton::{Automaton, Board};
+use gailibrary::engine::boundary::Boundary;
+use gailibrary::engine::life_rule::LifeRule;
+use gailibrary::examples::life_like::LifeLike;
+use gailibrary::fields::Pos2D;
+use gailibrary::visualisation::*;
+
+use std::env;
+use std::process;
+
+pub fn main() {
+    let window_width: usize = 800;
+    let window_height: usize = 600;
+    let width = 100;
+    let height = 75;
+
+    let rule_str = env::args().nth(1).unwrap_or_else(|| "B36/S23".to_string());
+    let rule: LifeRule = match rule_str.parse() {
+        Ok(rule) => rule,
+        Err(err) => {
+            eprintln!("Invalid rule \"{}\": {}", rule_str, err);
+            process::exit(1);
+        }
+    };
+    println!("Running {}", rule);
+
+    let c = conf::Conf::new();
+    let ctx = &mut Context::load_from_conf("life_like", "makingthematrix", c).unwrap();
+    setup(ctx, window_width, window_height);
+
+    let board = Board::from_fn(width, height, Boundary::Torus, |pos| {
+        LifeLike::dead(pos, rule)
+    });
+    let state =
+        &mut MainState::with_automaton(window_width, window_height, Automaton::from_board(board))
+            .unwrap();
+    state.add(&Pos2D::new((width as i64) / 2, (height as i64) / 2));
+    event::run(ctx, state).unwrap();
+}
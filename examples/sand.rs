@@ -0,0 +1,40 @@
+//! Falling sand, a block automaton on the Margolus neighbourhood. Click to pour more sand.
+extern crate ggez;
+
+use ggez::{conf, event, Context};
+
+use gailibrary::engine::automaton::{Automaton, AutomatonCell, Board};
+use gailibrary::engine::boundary::Boundary;
+use gailibrary::engine::margolus::Margolus;
+use gailibrary::examples::sand::Sand;
+use gailibrary::fields::Pos2D;
+use gailibrary::visualisation::*;
+
+use rand::Rng;
+
+pub fn main() {
+    let window_width: usize = 800;
+    let window_height: usize = 600;
+    let width = 100;
+    let height = 75;
+
+    let c = conf::Conf::new();
+    let ctx = &mut Context::load_from_conf("sand", "makingthematrix", c).unwrap();
+    setup(ctx, window_width, window_height);
+
+    let wall = Sand::new_grain(&Pos2D::new(-1, -1));
+    let mut rng = rand::thread_rng();
+    let board = Board::from_fn(width, height, Boundary::Wall(wall), |pos| {
+        if pos.y < height as i64 / 3 && rng.gen::<f64>() < 0.3 {
+            Sand::new_grain(pos)
+        } else {
+            Sand::new(pos)
+        }
+    });
+    let state =
+        &mut MainState::with_automaton(window_width, window_height, Automaton::from_board(board))
+            .unwrap();
+    let margolus = Margolus::new(Sand::fall);
+    state.set_step(move |board| margolus.update(board));
+    event::run(ctx, state).unwrap();
+}
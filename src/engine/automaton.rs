@@ -1,79 +1,430 @@
-use crate::fields::{Dir2D, Pos2D, DIRS4, DIRS8};
+use crate::engine::boundary::Boundary;
+use crate::engine::grid::{self, Grid};
+use crate::engine::observer::{ObserverId, Observers};
+use crate::engine::snapshot::{Snapshot, SnapshotError};
+use crate::engine::stability::{CycleDetector, Stability};
+use crate::engine::statistics::{Recorder, Statistics};
+use crate::fields::{Dir2D, Dir6, Pos2D, DIRS4, DIRS6, DIRS8};
+use crate::utils::prng::{mix_seed, PseudoNormalRng};
 use crate::utils::umap::UMap;
+use crate::utils::uset::USet;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
 
 use crate::utils::umap::UMapIter;
-use std::collections::HashMap;
+use std::cmp::max;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Index;
+use std::time::{Duration, Instant};
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 
-pub trait AutomatonCell: Clone + Copy + PartialEq + Sized + Debug {
+pub trait AutomatonCell: Clone + Copy + PartialEq + Sized + Debug + Send + Sync {
     fn update(&self, neighborhood: &Neighborhood<Self>) -> Self;
     fn position(&self) -> Pos2D;
 
     fn new(pos: &Pos2D) -> Self;
+
+    /// A quiescent cell surrounded only by quiescent cells doesn't change, so with active-cell
+    /// tracking on (see `Automaton::set_active_tracking`) the engine may skip it.
+    fn is_quiescent(&self) -> bool {
+        false
+    }
+
+    /// How far `update` looks: cells further away than `radius` in any direction never influence it.
+    fn radius(&self) -> usize {
+        1
+    }
 }
 
-pub trait Neighborhood<C: AutomatonCell> {
+pub trait Neighborhood<C: AutomatonCell>: AsNeighborhood<C> {
     fn find_cell(&self, pos: &Pos2D) -> &C;
 
-    fn neumann(&self, pos: &Pos2D) -> HashMap<Dir2D, &C> {
-        let mut map = HashMap::with_capacity(4);
-        DIRS4.iter().for_each(|&dir| {
-            map.insert(dir, self.find_cell(&pos.move_by_one(dir)));
-        });
-        map
+    /// The seed of the automaton (see `Automaton::set_seed`).
+    fn seed(&self) -> u64 {
+        0
     }
 
-    fn moore(&self, pos: &Pos2D) -> HashMap<Dir2D, &C> {
-        let mut map = HashMap::with_capacity(8);
-        DIRS8.iter().for_each(|&dir| {
-            map.insert(dir, self.find_cell(&pos.move_by_one(dir)));
-        });
-        map
+    /// The number of the generation being updated.
+    fn generation(&self) -> u64 {
+        0
+    }
+
+    /// Random numbers for the cell at `pos`. They depend only on the seed, the generation and
+    /// the position, so the same seed gives the same results every time, in any update mode.
+    fn rng(&self, pos: &Pos2D) -> XorShiftRng {
+        XorShiftRng::from_seed(mix_seed(&[
+            self.seed(),
+            self.generation(),
+            pos.x as u64,
+            pos.y as u64,
+        ]))
+    }
+
+    /// Like `rng`, but skewed towards the middle of the range (see `PseudoNormalRng`).
+    fn pseudo_normal_rng(&self, pos: &Pos2D, a: f64) -> PseudoNormalRng {
+        PseudoNormalRng::with_seed(
+            a,
+            mix_seed(&[self.seed(), self.generation(), pos.x as u64, pos.y as u64]),
+        )
+    }
+
+    /// The four orthogonal neighbours of `pos`, in the order of `DIRS4`.
+    fn neumann(&self, pos: &Pos2D) -> Neighbors<'_, C, 4> {
+        Neighbors {
+            cells: std::array::from_fn(|i| self.find_cell(&pos.move_by_one(DIRS4[i]))),
+            dirs: PhantomData,
+        }
+    }
+
+    /// All eight neighbours of `pos`, in the order of `DIRS8`.
+    fn moore(&self, pos: &Pos2D) -> Neighbors<'_, C, 8> {
+        Neighbors {
+            cells: std::array::from_fn(|i| self.find_cell(&pos.move_by_one(DIRS8[i]))),
+            dirs: PhantomData,
+        }
+    }
+
+    /// All the cells at most `radius` steps away from `pos` in any direction (a square), row by row,
+    /// without the cell at `pos` itself.
+    fn moore_radius(&self, pos: &Pos2D, radius: usize) -> RadiusCells<'_, C> {
+        RadiusCells::new(self.as_neighborhood(), pos, radius, false)
+    }
+
+    /// All the cells at most `radius` orthogonal steps away from `pos` (a diamond), row by row,
+    /// without the cell at `pos` itself.
+    fn neumann_radius(&self, pos: &Pos2D, radius: usize) -> RadiusCells<'_, C> {
+        RadiusCells::new(self.as_neighborhood(), pos, radius, true)
+    }
+
+    /// The six neighbours of `pos` on a hex grid (see `fields::Hex` for the layout), in the order
+    /// of `DIRS6`. Wrapping a hex board vertically works only if its height is even.
+    fn hex(&self, pos: &Pos2D) -> Neighbors<'_, C, 6, Dir6> {
+        Neighbors {
+            cells: std::array::from_fn(|i| self.find_cell(&pos.move_by_one_hex(DIRS6[i]))),
+            dirs: PhantomData,
+        }
     }
 }
 
-#[derive(Default, Clone)]
+/// Lets the default methods of `Neighborhood` pass the neighbourhood on as a trait object.
+/// It's implemented for every `Neighborhood`.
+pub trait AsNeighborhood<C: AutomatonCell> {
+    fn as_neighborhood(&self) -> &dyn Neighborhood<C>;
+}
+
+impl<C: AutomatonCell, H: Neighborhood<C>> AsNeighborhood<C> for H {
+    #[inline]
+    fn as_neighborhood(&self) -> &dyn Neighborhood<C> {
+        self
+    }
+}
+
+/// The directions `Neighbors` can be indexed with: `Dir2D` on a square grid and `Dir6` on a hex one.
+pub trait NeighborDir: Copy + 'static {
+    /// All the directions, in the order of the neighbours.
+    fn all() -> &'static [Self];
+    fn index(self) -> usize;
+}
+
+impl NeighborDir for Dir2D {
+    #[inline]
+    fn all() -> &'static [Dir2D] {
+        &*DIRS8
+    }
+
+    #[inline]
+    fn index(self) -> usize {
+        Dir2D::index(self)
+    }
+}
+
+impl NeighborDir for Dir6 {
+    #[inline]
+    fn all() -> &'static [Dir6] {
+        &*DIRS6
+    }
+
+    #[inline]
+    fn index(self) -> usize {
+        Dir6::index(self)
+    }
+}
+
+/// The neighbours of a cell, as returned by `Neighborhood::neumann`, `moore` and `hex`:
+/// a fixed-size array in the order of `DIRS4`, `DIRS8` or `DIRS6`, so getting it doesn't allocate
+/// anything. Indexing it with a diagonal direction panics if it holds only the four orthogonal
+/// neighbours.
+#[derive(Debug, Clone, Copy)]
+pub struct Neighbors<'a, C, const N: usize, D = Dir2D> {
+    cells: [&'a C; N],
+    dirs: PhantomData<D>,
+}
+
+impl<'a, C, const N: usize, D: NeighborDir> Neighbors<'a, C, N, D> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    #[inline]
+    pub fn get(&self, dir: D) -> Option<&'a C> {
+        self.cells.get(dir.index()).copied()
+    }
+
+    /// The directions and the cells in them.
+    pub fn iter(&self) -> impl Iterator<Item = (D, &'a C)> + '_ {
+        D::all().iter().copied().zip(self.cells.iter().copied())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &'a C> + '_ {
+        self.cells.iter().copied()
+    }
+}
+
+impl<'a, C, const N: usize, D: NeighborDir> Index<D> for Neighbors<'a, C, N, D> {
+    type Output = C;
+
+    #[inline]
+    fn index(&self, dir: D) -> &C {
+        self.cells[dir.index()]
+    }
+}
+
+/// The cells around a position, as returned by `Neighborhood::moore_radius` and `neumann_radius`.
+/// They are found one by one, row by row, so nothing is allocated.
+pub struct RadiusCells<'a, C: AutomatonCell> {
+    neighborhood: &'a dyn Neighborhood<C>,
+    pos: Pos2D,
+    radius: i64,
+    diamond: bool,
+    // the offset of the next cell
+    x: i64,
+    y: i64,
+}
+
+impl<'a, C: AutomatonCell> RadiusCells<'a, C> {
+    fn new(
+        neighborhood: &'a dyn Neighborhood<C>,
+        pos: &Pos2D,
+        radius: usize,
+        diamond: bool,
+    ) -> Self {
+        let radius = radius as i64;
+        let mut cells = RadiusCells {
+            neighborhood,
+            pos: *pos,
+            radius,
+            diamond,
+            x: 0,
+            y: -radius,
+        };
+        cells.x = -cells.half_width(cells.y);
+        cells
+    }
+
+    // How far the row `y` reaches to the left and to the right.
+    #[inline]
+    fn half_width(&self, y: i64) -> i64 {
+        if self.diamond {
+            self.radius - y.abs()
+        } else {
+            self.radius
+        }
+    }
+}
+
+impl<'a, C: AutomatonCell> Iterator for RadiusCells<'a, C> {
+    type Item = &'a C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.y <= self.radius {
+            if self.x > self.half_width(self.y) {
+                self.y += 1;
+                self.x = -self.half_width(self.y);
+                continue;
+            }
+            let (x, y) = (self.x, self.y);
+            self.x += 1;
+            if x != 0 || y != 0 {
+                let pos = Pos2D::new(self.pos.x + x, self.pos.y + y);
+                return Some(self.neighborhood.find_cell(&pos));
+            }
+        }
+        None
+    }
+}
+
+/// Two boards are equal if they have the same size, boundary and cells, even if they were
+/// computed with different seeds or are in different generations.
+#[derive(Debug, Default, Clone)]
 pub struct Board<C: AutomatonCell> {
-    dim: usize,
+    width: usize,
+    height: usize,
+    boundary: Boundary<C>,
     map: UMap<C>,
+    seed: u64,
+    generation: u64,
 }
 
 impl<C: AutomatonCell> Board<C> {
-    fn pos2id(dim: usize, pos: &Pos2D) -> usize {
-        fn wrap(i: i64, dim: usize) -> usize {
-            match i % dim as i64 {
-                x if x >= 0 => x as usize,
-                x => (x + dim as i64) as usize,
+    #[inline]
+    fn pos2id(width: usize, pos: &Pos2D) -> usize {
+        pos.y as usize * width + pos.x as usize
+    }
+
+    #[inline]
+    fn id(&self, pos: &Pos2D) -> Option<usize> {
+        self.boundary
+            .resolve(self.width, self.height, pos)
+            .map(|p| Board::<C>::pos2id(self.width, &p))
+    }
+
+    #[inline]
+    fn id2pos(&self, id: usize) -> Pos2D {
+        Pos2D::new((id % self.width) as i64, (id / self.width) as i64)
+    }
+
+    /// Adds to `set` the cell `id` and all the cells which can see it from their neighbourhoods.
+    fn mark_neighbourhood(&self, id: usize, set: &mut USet) {
+        let pos = self.id2pos(id);
+        let r = self.map.get_ref(id).unwrap().radius() as i64;
+        for y in pos.y - r..=pos.y + r {
+            for x in pos.x - r..=pos.x + r {
+                if let Some(id) = self.id(&Pos2D::new(x, y)) {
+                    set.add(id);
+                }
             }
         }
+    }
+
+    /// The cells which aren't quiescent, together with their neighbourhoods.
+    fn active_cells(&self) -> USet {
+        let mut set = USet::with_capacity(self.map.capacity());
+        self.map
+            .iter()
+            .filter(|(_, cell)| !cell.is_quiescent())
+            .for_each(|(id, _)| self.mark_neighbourhood(id, &mut set));
+        set
+    }
+
+    pub fn new(width: usize, height: usize) -> Self {
+        Board::with_boundary(width, height, Boundary::Torus)
+    }
 
-        wrap(pos.x, dim) * dim + wrap(pos.y, dim)
+    pub fn with_boundary(width: usize, height: usize, boundary: Boundary<C>) -> Self {
+        Board::from_fn(width, height, boundary, C::new)
     }
 
-    pub fn new(dim: usize) -> Self {
-        let mut map = UMap::<C>::with_capacity(dim);
-        Pos2D::from_dim(dim)
+    /// A board with the cells made by `f` instead of `AutomatonCell::new`, for cells which need
+    /// more than their position, e.g. a rule.
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        boundary: Boundary<C>,
+        mut f: impl FnMut(&Pos2D) -> C,
+    ) -> Self {
+        let mut map = UMap::<C>::with_capacity(width * height);
+        Pos2D::from_dims(width, height)
             .iter()
-            .for_each(|pos| map.put(Board::<C>::pos2id(dim, pos), C::new(pos)));
+            .for_each(|pos| map.put(Board::<C>::pos2id(width, pos), f(pos)));
 
-        Board { dim, map }
+        Board {
+            width,
+            height,
+            boundary,
+            map,
+            seed: 0,
+            generation: 0,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn boundary(&self) -> &Boundary<C> {
+        &self.boundary
+    }
+
+    /// Makes the board pretend it was updated `generation` times, e.g. after updating it
+    /// without `Board::update`.
+    #[inline]
+    pub(crate) fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The cell at `pos`, after applying the boundary, or `None` if `pos` is behind a wall.
+    #[inline]
+    pub fn get(&self, pos: &Pos2D) -> Option<&C> {
+        self.id(pos).map(|id| self.map.get_ref(id).unwrap())
+    }
+
+    #[inline]
+    pub fn iter(&self) -> UMapIter<'_, C> {
+        self.map.iter()
     }
 
     pub fn update(&self) -> Self {
-        //let mut updates = Duration::new(0, 0);
-        let mut map = UMap::<C>::with_capacity(self.dim);
-        self.map.iter().for_each(|(key, cell)| {
-            //let t0 = Instant::now();
-            let new_cell = cell.update(self);
-            //let t1 = Instant::now();
-            map.put(key, new_cell);
-            //updates += t1 - t0;
-        });
+        let mut next = self.clone();
+        self.update_into(&mut next);
+        next
+    }
 
-        //println!("updates took {:?}", updates);
+    /// Computes the next generation into `target`, reusing its storage instead of allocating a new board.
+    pub fn update_into(&self, target: &mut Board<C>) {
+        target.width = self.width;
+        target.height = self.height;
+        target.boundary = self.boundary;
+        target.seed = self.seed;
+        target.generation = self.generation + 1;
+        grid::update_into(self, target);
+    }
+
+    pub fn update_parallel(&self, threads: usize) -> Self {
+        let mut next = self.clone();
+        self.update_parallel_into(&mut next, threads);
+        next
+    }
 
-        Board { dim: self.dim, map }
+    /// Computes the next generation just like `update_into`, but splits the board into `threads`
+    /// bands of rows and updates each band in a separate thread. Every cell reads only the old
+    /// board, so the result is exactly the same as the one of `update_into`. No threads at all
+    /// means one.
+    pub fn update_parallel_into(&self, target: &mut Board<C>, threads: usize) {
+        let threads = threads.max(1);
+        target.width = self.width;
+        target.height = self.height;
+        target.boundary = self.boundary;
+        target.seed = self.seed;
+        target.generation = self.generation + 1;
+
+        let band = max(1, (self.height + threads - 1) / threads);
+        target
+            .map
+            .put_all_parallel(self.map.capacity(), band * self.width, |id| {
+                self.map.get_ref(id).unwrap().update(self)
+            });
     }
 
     pub fn copy_and_update_one(&self, new_cell: &C) -> Self {
@@ -81,83 +432,934 @@ impl<C: AutomatonCell> Board<C> {
     }
 
     pub fn copy_and_update(&self, cells: &[C]) -> Self {
-        let mut map = UMap::<C>::with_capacity(self.dim);
-        map.clone_from(&self.map);
+        grid::copy_and_update(self, cells)
+    }
 
-        for c in cells {
-            let id = Board::<C>::pos2id(self.dim, &c.position());
-            map.put(id, *c);
+    /// The cells which are different on `other`, a board of the same size, e.g. a later
+    /// generation of this one.
+    /// Panics if the sizes of the boards are different.
+    pub fn diff(&self, other: &Board<C>) -> BoardDiff<C> {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "boards of different sizes can't be compared"
+        );
+        let ids: Vec<usize> = self
+            .map
+            .iter()
+            .filter(|&(id, cell)| other.map.get_ref(id) != Some(cell))
+            .map(|(id, _)| id)
+            .collect();
+        // a subset of an empty set is the whole map
+        let (old, new) = if ids.is_empty() {
+            (UMap::new(), UMap::new())
+        } else {
+            let ids = USet::from(ids);
+            (self.map.subset(&ids), other.map.subset(&ids))
+        };
+        BoardDiff {
+            width: self.width,
+            generations: (self.generation, other.generation),
+            old,
+            new,
         }
-
-        Board { dim: self.dim, map }
     }
 
     pub fn copy_and_update_2(&self, cells: &UMap<C>) -> Self {
-        let mut map = UMap::<C>::with_capacity(self.dim);
-        map.clone_from(&self.map);
-        cells.iter().for_each(|(id, cell)| map.put(id, *cell));
-        Board { dim: self.dim, map }
+        grid::copy_and_put(self, cells)
+    }
+}
+
+impl<C: AutomatonCell> Grid for Board<C> {
+    type Cell = C;
+    type Pos = Pos2D;
+
+    #[inline]
+    fn id(&self, pos: &Pos2D) -> Option<usize> {
+        Board::id(self, pos)
+    }
+
+    #[inline]
+    fn position(cell: &C) -> Pos2D {
+        cell.position()
+    }
+
+    #[inline]
+    fn cells(&self) -> &UMap<C> {
+        &self.map
+    }
+
+    #[inline]
+    fn cells_mut(&mut self) -> &mut UMap<C> {
+        &mut self.map
+    }
+
+    #[inline]
+    fn update_cell(&self, cell: &C) -> C {
+        cell.update(self)
+    }
+}
+
+/// The size, the boundary and the cells are compared, but not the seed or the generation.
+impl<C: AutomatonCell> PartialEq for Board<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.boundary == other.boundary
+            && self.map == other.map
+    }
+}
+
+/// Only the size and the cells of the board are hashed, not its generation or seed.
+impl<C: AutomatonCell + Hash> Hash for Board<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        self.map.iter().for_each(|(_, cell)| cell.hash(state));
+    }
+}
+
+/// The cells which are different on two boards, as returned by `Board::diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardDiff<C: AutomatonCell> {
+    width: usize,
+    generations: (u64, u64),
+    old: UMap<C>,
+    new: UMap<C>,
+}
+
+impl<C: AutomatonCell> BoardDiff<C> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.old.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.old.is_empty()
+    }
+
+    /// The generations of the old and the new board.
+    #[inline]
+    pub fn generations(&self) -> (u64, u64) {
+        self.generations
+    }
+
+    /// The positions of the different cells and the cells on the old and the new board, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos2D, &C, &C)> + '_ {
+        let width = self.width;
+        self.old
+            .iter()
+            .zip(self.new.iter())
+            .map(move |((id, old), (_, new))| {
+                let pos = Pos2D::new((id % width) as i64, (id / width) as i64);
+                (pos, old, new)
+            })
+    }
+
+    pub fn positions(&self) -> Vec<Pos2D> {
+        self.iter().map(|(pos, _, _)| pos).collect()
+    }
+
+    /// Turns the old board into the new one, e.g. to replay a board sent as a sequence of diffs.
+    pub fn apply(&self, board: &Board<C>) -> Board<C> {
+        let mut next = board.copy_and_update_2(&self.new);
+        next.generation = self.generations.1;
+        next
+    }
+
+    /// Turns the new board back into the old one.
+    pub fn revert(&self, board: &Board<C>) -> Board<C> {
+        let mut previous = board.copy_and_update_2(&self.old);
+        previous.generation = self.generations.0;
+        previous
     }
 }
 
 impl<C: AutomatonCell> Neighborhood<C> for Board<C> {
     fn find_cell(&self, pos: &Pos2D) -> &C {
-        self.map.get_ref(Board::<C>::pos2id(self.dim, pos)).unwrap()
+        match self.id(pos) {
+            Some(id) => self.map.get_ref(id).unwrap(),
+            None => self.boundary.wall().unwrap(),
+        }
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
     }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+/// How `Automaton::next` computes the next generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateMode {
+    /// All cells are updated one after another in the current thread.
+    #[default]
+    Serial,
+    /// The board is split into bands of rows, each updated in its own thread (see `Board::update_parallel`).
+    Parallel(usize),
+}
+
+/// In what order `Automaton::next` updates the cells.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Schedule {
+    /// All cells are updated at once: each of them sees only the previous generation.
+    #[default]
+    Synchronous,
+    /// The cells are updated one by one, in a new random order every generation, each seeing
+    /// the cells updated before it. The order depends only on the board's seed and the generation.
+    RandomSequential,
+    /// The cells are updated one by one, row by row, from the top left corner, each seeing
+    /// the cells updated before it.
+    Sweep,
+    /// Every cell is updated with the given probability, and otherwise stays as it is. Like in
+    /// the synchronous schedule, the updated cells see only the previous generation.
+    RandomIndependent(f64),
 }
 
 #[derive(Default, Clone)]
 pub struct Automaton<C: AutomatonCell> {
     board: Board<C>,
+    // the previous generation; the next one is computed into it and then the two are swapped
+    back: Board<C>,
     changes: UMap<C>,
+    mode: UpdateMode,
+    schedule: Schedule,
+    // the order of updates in the random sequential schedule, kept to reuse the memory
+    order: Vec<usize>,
+    // cells to update in the next generation, if active-cell tracking is on
+    active: Option<USet>,
+    // ids of the cells which changed in the last generation
+    changed: Vec<usize>,
+    // the oldest frames first
+    history: VecDeque<Frame<C>>,
+    history_length: usize,
+    cycles: Option<CycleDetector<C>>,
+    // the cells from before the last change of the board, by `next`, `apply_changes`,
+    // `transform` or `rewind_to`, which might be different now
+    events: UMap<C>,
+    observers: Observers<C>,
+    // the cells changed by the current step, kept to reuse the memory
+    step: Vec<(usize, C)>,
+    statistics: Option<Box<dyn Recorder<C>>>,
+    // the cells from before the changes applied by `apply_changes` since the last sample of
+    // the statistics, which the events of the next generation won't have anymore
+    unsampled: UMap<C>,
+}
+
+// What `Automaton::rewind_to` needs to undo one change of the board: the cells from before it,
+// but only those which were different after it, and the pending changes applied by it.
+#[derive(Debug, Clone)]
+struct Frame<C: AutomatonCell> {
+    generation: u64,
+    cells: Vec<(usize, C)>,
+    changes: Vec<(usize, C)>,
+}
+
+// Puts into `cells` the cells of `old` which are different in `new`.
+fn diff_into<C: AutomatonCell>(old: &Board<C>, new: &Board<C>, cells: &mut Vec<(usize, C)>) {
+    cells.clear();
+    cells.extend(
+        old.map
+            .iter()
+            .filter(|&(id, cell)| new.map.get_ref(id) != Some(cell))
+            .map(|(id, cell)| (id, *cell)),
+    );
 }
 
 impl<C: AutomatonCell> Automaton<C> {
-    pub fn new(dim: usize) -> Automaton<C> {
+    pub fn new(width: usize, height: usize) -> Automaton<C> {
+        Automaton::with_boundary(width, height, Boundary::Torus)
+    }
+
+    pub fn with_boundary(width: usize, height: usize, boundary: Boundary<C>) -> Automaton<C> {
+        Automaton::from_board(Board::<C>::with_boundary(width, height, boundary))
+    }
+
+    pub fn from_board(board: Board<C>) -> Automaton<C> {
+        let (width, height) = (board.width, board.height);
         Automaton {
-            board: Board::<C>::new(dim),
-            changes: UMap::<C>::with_capacity(dim),
+            back: board.clone(),
+            board,
+            changes: UMap::<C>::with_capacity(width * height),
+            mode: UpdateMode::Serial,
+            schedule: Schedule::Synchronous,
+            order: Vec::new(),
+            active: None,
+            changed: Vec::new(),
+            history: VecDeque::new(),
+            history_length: 0,
+            cycles: None,
+            events: UMap::<C>::with_capacity(width * height),
+            observers: Observers::default(),
+            step: Vec::with_capacity(width * height),
+            statistics: None,
+            unsampled: UMap::new(),
         }
     }
 
     pub fn next(&mut self) {
-        self.apply_changes();
-        self.board = self.board.update();
+        self.events.clear();
+        self.apply_pending_changes();
+        let start = Instant::now();
+        match self.schedule {
+            Schedule::Synchronous => self.update_synchronous(),
+            Schedule::RandomSequential => {
+                let mut order = mem::take(&mut self.order);
+                order.clear();
+                order.extend(0..self.board.map.capacity());
+                self.rng().shuffle(&mut order);
+                self.update_in_order(order.iter().copied());
+                self.order = order;
+            }
+            Schedule::Sweep => self.update_in_order(0..self.board.map.capacity()),
+            Schedule::RandomIndependent(p) => self.update_randomly(p),
+        }
+        self.finish_generation(start.elapsed());
+    }
+
+    /// Computes the next generation with `update` instead of the rules of the cells, e.g.
+    /// `auto.next_with(|board| margolus.update(board))`. Otherwise it's like `next`: the changes
+    /// are applied first, and the history, the cycle detection, the statistics and the observers
+    /// all see a new generation. The board returned by `update` keeps the seed and gets
+    /// the next generation number, whatever it had before.
+    pub fn next_with(&mut self, update: impl FnOnce(&Board<C>) -> Board<C>) {
+        self.events.clear();
+        self.apply_pending_changes();
+        let start = Instant::now();
+        let mut board = update(&self.board);
+        let update_time = start.elapsed();
+
+        board.seed = self.board.seed;
+        board.generation = self.board.generation + 1;
+        diff_into(&self.board, &board, &mut self.step);
+        self.finish_step(self.board.generation);
+        self.board = board;
+        self.refresh_active_cells();
+        self.finish_generation(update_time);
+    }
+
+    // What every generation ends with, however it was computed.
+    fn finish_generation(&mut self, update_time: Duration) {
+        if let Some(cycles) = &mut self.cycles {
+            cycles.observe(&self.board);
+        }
+        self.record_statistics(update_time);
+        self.notify_observers();
+    }
+
+    fn update_synchronous(&mut self) {
+        if self.active.is_some() {
+            self.update_active();
+        } else {
+            match self.mode {
+                UpdateMode::Serial => self.board.update_into(&mut self.back),
+                UpdateMode::Parallel(threads) => {
+                    self.board.update_parallel_into(&mut self.back, threads)
+                }
+            };
+        }
+        mem::swap(&mut self.board, &mut self.back);
+
+        // `back` holds the previous generation now
+        self.step.clear();
+        if self.active.is_some() {
+            let back = &self.back;
+            self.step.extend(
+                self.changed
+                    .iter()
+                    .map(|&id| (id, back.map.get(id).unwrap())),
+            );
+        } else {
+            diff_into(&self.back, &self.board, &mut self.step);
+        }
+        self.finish_step(self.back.generation);
+    }
+
+    // The random numbers for the current generation.
+    fn rng(&self) -> XorShiftRng {
+        XorShiftRng::from_seed(mix_seed(&[self.board.seed, self.board.generation]))
+    }
+
+    // Updates the cells in place, so every one of them sees the ones updated before it.
+    fn update_in_order(&mut self, ids: impl Iterator<Item = usize>) {
+        self.step.clear();
+        for id in ids {
+            let cell = *self.board.map.get_ref(id).unwrap();
+            let new_cell = cell.update(&self.board);
+            if new_cell != cell {
+                self.step.push((id, cell));
+            }
+            self.board.map.put(id, new_cell);
+        }
+        self.finish_step(self.board.generation);
+        self.board.generation += 1;
+        self.refresh_active_cells();
+    }
+
+    fn update_randomly(&mut self, p: f64) {
+        let mut rng = self.rng();
+        let (board, back) = (&self.board, &mut self.back);
+        board.map.iter().for_each(|(id, cell)| {
+            let new_cell = if rng.gen::<f64>() < p {
+                cell.update(board)
+            } else {
+                *cell
+            };
+            back.map.put(id, new_cell);
+        });
+        self.back.generation = self.board.generation + 1;
+        mem::swap(&mut self.board, &mut self.back);
+        diff_into(&self.back, &self.board, &mut self.step);
+        self.finish_step(self.back.generation);
+        self.refresh_active_cells();
+    }
+
+    // Asynchronous schedules don't keep track of what changed, so the active cells have to be
+    // found again from scratch.
+    fn refresh_active_cells(&mut self) {
+        if self.active.is_some() {
+            self.set_active_tracking(true);
+        }
+    }
+
+    #[inline]
+    pub fn schedule(&self) -> Schedule {
+        self.schedule
+    }
+
+    /// The update mode and active-cell tracking make a difference only with the synchronous
+    /// schedule. The others update the cells serially, and with tracking on they look for active
+    /// cells on the whole board after every generation.
+    #[inline]
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = schedule;
+    }
+
+    /// The seed for the random choices of the schedules and for `Neighborhood::rng`.
+    /// It's 0 by default, so without calling this the results are the same in every run.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.board.seed = seed;
+        self.back.seed = seed;
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.board.seed
+    }
+
+    // Updates only the active cells. All the other ones are the same in both buffers: they didn't
+    // change in the last generation, or they would be active now.
+    fn update_active(&mut self) {
+        let active = self.active.as_mut().unwrap();
+        self.changed.clear();
+        for id in active.iter() {
+            let cell = self.board.map.get_ref(id).unwrap();
+            let new_cell = cell.update(&self.board);
+            if new_cell != *cell {
+                self.changed.push(id);
+            }
+            self.back.map.put(id, new_cell);
+        }
+        self.back.generation = self.board.generation + 1;
+
+        active.clear();
+        for &id in &self.changed {
+            self.back.mark_neighbourhood(id, active);
+        }
+    }
+
+    /// Turns active-cell tracking on or off. With tracking on, `next` updates only the cells
+    /// which changed in the previous generation (or through `apply_changes`), and the cells in their
+    /// neighbourhoods. Right after turning it on, those are the cells which aren't quiescent (see
+    /// `AutomatonCell::is_quiescent`) and their neighbourhoods. The active cells are always updated
+    /// serially, regardless of the update mode.
+    pub fn set_active_tracking(&mut self, enabled: bool) {
+        self.active = if enabled {
+            self.back = self.board.clone();
+            Some(self.board.active_cells())
+        } else {
+            None
+        };
+    }
+
+    /// The cells which will be updated in the next generation, or `None` if active-cell tracking is off.
+    #[inline]
+    pub fn active_cells(&self) -> Option<&USet> {
+        self.active.as_ref()
+    }
+
+    #[inline]
+    pub fn update_mode(&self) -> UpdateMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn set_update_mode(&mut self, mode: UpdateMode) {
+        self.mode = mode;
     }
 
     pub fn transform(&mut self, f: impl Fn(&Board<C>) -> Board<C>) {
-        self.board = f(&self.board);
+        let start = Instant::now();
+        let board = f(&self.board);
+        let update_time = start.elapsed();
+        self.events.clear();
+        diff_into(&self.board, &board, &mut self.step);
+        self.finish_step(self.board.generation);
+        self.board = board;
+        if self.active.is_some() {
+            self.set_active_tracking(true);
+        }
+        self.restart_cycle_detection();
+        self.record_statistics(update_time);
+        self.notify_observers();
     }
 
     pub fn add_change(&mut self, changed_cell: &C) {
-        self.changes.put(
-            Board::<C>::pos2id(self.dim(), &changed_cell.position()),
-            *changed_cell,
-        );
+        grid::add_change(&self.board, &mut self.changes, changed_cell);
     }
 
+    /// Puts the cells added with `add_change` on the board. It's done at the beginning of `next`
+    /// anyway, so this is needed only to see them before the next generation.
     pub fn apply_changes(&mut self) {
-        let cs = self.changes.clone();
-        self.board = self.board.copy_and_update_2(&cs);
-        self.changes = UMap::<C>::with_capacity(self.dim()); // TODO: implement `clear` for `UMap`
+        if !self.changes.is_empty() {
+            self.events.clear();
+            self.apply_pending_changes();
+            if self.statistics.is_some() {
+                for (id, cell) in self.events.iter() {
+                    if !self.unsampled.contains(id) {
+                        self.unsampled.put(id, *cell);
+                    }
+                }
+            }
+            self.notify_observers();
+        }
+    }
+
+    fn apply_pending_changes(&mut self) {
+        if self.changes.is_empty() {
+            return;
+        }
+        let board = &self.board;
+        let mut cells = mem::take(&mut self.step);
+        cells.clear();
+        cells.extend(
+            self.changes
+                .iter()
+                .map(|(id, _)| (id, board.map.get(id).unwrap())),
+        );
+        self.note_events(&cells);
+        if self.history_length > 0 {
+            let changes: Vec<(usize, C)> =
+                self.changes.iter().map(|(id, cell)| (id, *cell)).collect();
+            self.record(self.board.generation, &cells, &changes);
+        }
+        self.step = cells;
+
+        let active = &mut self.active;
+        grid::apply_changes(&mut self.board, &mut self.changes, |board, id| {
+            if let Some(set) = active {
+                board.mark_neighbourhood(id, set);
+            }
+        });
+        self.restart_cycle_detection();
+    }
+
+    /// How the board settled, if cycle detection is on (see `set_cycle_detection`) and the board
+    /// has already started repeating itself.
+    pub fn stability(&self) -> Option<Stability> {
+        self.cycles.as_ref().and_then(CycleDetector::stability)
+    }
+
+    /// The cells changed by the last change of the board: by the last generation computed by
+    /// `next` (together with the changes it applied), or by `apply_changes`, `transform` or
+    /// `rewind_to`, whichever was last. Returns their positions and the old and new cells,
+    /// row by row.
+    pub fn last_changes(&self) -> impl Iterator<Item = (Pos2D, &C, &C)> + '_ {
+        let board = &self.board;
+        self.events.iter().filter_map(move |(id, old)| {
+            let new = board.map.get_ref(id).unwrap();
+            if old != new {
+                Some((board.id2pos(id), old, new))
+            } else {
+                None
+            }
+        })
     }
 
+    /// The positions of the cells changed by the last change of the board (see `last_changes`).
+    pub fn changed_positions(&self) -> Vec<Pos2D> {
+        self.last_changes().map(|(pos, _, _)| pos).collect()
+    }
+
+    /// Registers `callback` to be called for every cell changed from now on, by `next`,
+    /// `apply_changes`, `transform` or `rewind_to`, if `filter` accepts the old and the new cell.
+    /// The callback gets the position and both cells, after the whole board is changed.
+    /// Both closures have to be `Send` and `Sync`, so that the automaton still is.
+    pub fn observe(
+        &mut self,
+        filter: impl Fn(&C, &C) -> bool + Send + Sync + 'static,
+        callback: impl FnMut(&Pos2D, &C, &C) + Send + Sync + 'static,
+    ) -> ObserverId {
+        self.observers.add(filter, callback)
+    }
+
+    /// Returns false if there's no such observer.
+    pub fn remove_observer(&mut self, id: ObserverId) -> bool {
+        self.observers.remove(id)
+    }
+
+    /// Attaches `statistics`, which record a sample of the current board right away and then
+    /// one after every generation computed by `next` (and after `transform` and `rewind_to`,
+    /// see `Statistics`). Replaces the statistics attached before, if any.
+    pub fn set_statistics<K>(&mut self, statistics: Statistics<C, K>)
+    where
+        C: 'static,
+        K: Hash + Eq + Clone + Display + Send + Sync + 'static,
+    {
+        self.statistics = Some(Box::new(statistics));
+        self.record_statistics(Duration::default());
+    }
+
+    /// The attached statistics, or `None` if there are none or their categories are not `K`.
+    pub fn statistics<K>(&self) -> Option<&Statistics<C, K>>
+    where
+        C: 'static,
+        K: Hash + Eq + Clone + Display + Send + Sync + 'static,
+    {
+        self.statistics.as_ref()?.as_any().downcast_ref()
+    }
+
+    /// Detaches the statistics and returns them, unless their categories are not `K`.
+    pub fn take_statistics<K>(&mut self) -> Option<Statistics<C, K>>
+    where
+        C: 'static,
+        K: Hash + Eq + Clone + Display + Send + Sync + 'static,
+    {
+        self.statistics::<K>()?;
+        let statistics = self.statistics.take()?.into_any();
+        statistics.downcast().ok().map(|statistics| *statistics)
+    }
+
+    /// Detaches the statistics, whatever their categories.
+    pub fn remove_statistics(&mut self) {
+        self.statistics = None;
+    }
+
+    fn record_statistics(&mut self, update_time: Duration) {
+        if self.statistics.is_none() {
+            return;
+        }
+        // the older cells from `unsampled` go first
+        let (board, unsampled) = (&self.board, &self.unsampled);
+        let changed = unsampled
+            .iter()
+            .chain(
+                self.events
+                    .iter()
+                    .filter(|&(id, _)| !unsampled.contains(id)),
+            )
+            .filter(|&(id, old)| board.map.get_ref(id) != Some(old))
+            .count();
+        self.unsampled.clear();
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record(&self.board, changed, update_time);
+        }
+    }
+
+    // Remembers the old cells, unless they were already changed earlier in the same step.
+    fn note_events(&mut self, cells: &[(usize, C)]) {
+        for &(id, cell) in cells {
+            if !self.events.contains(id) {
+                self.events.put(id, cell);
+            }
+        }
+    }
+
+    fn notify_observers(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let (board, observers) = (&self.board, &mut self.observers);
+        for (id, old) in self.events.iter() {
+            let new = board.map.get_ref(id).unwrap();
+            if old != new {
+                observers.notify(&board.id2pos(id), old, new);
+            }
+        }
+    }
+
+    // Changes from the outside break the sequence of generations, so the detection has to start
+    // over from the current board.
+    fn restart_cycle_detection(&mut self) {
+        if let Some(cycles) = &mut self.cycles {
+            cycles.reset();
+            cycles.observe(&self.board);
+        }
+    }
+
+    /// How many past generations are kept, so that it's possible to go back to them with
+    /// `rewind_to` or `step_back`. It's 0 by default, which turns the history off.
     #[inline]
-    pub fn dim(&self) -> usize {
-        self.board.dim
+    pub fn history_length(&self) -> usize {
+        self.history_length
+    }
+
+    /// Only the cells which changed are kept for every generation, so a long history of a board
+    /// where little happens takes little memory. Shortening the history drops the oldest generations.
+    pub fn set_history_length(&mut self, length: usize) {
+        self.history_length = length;
+        if length == 0 {
+            self.history.clear();
+        }
+        self.trim_history();
+    }
+
+    /// The oldest generation it's possible to go back to.
+    pub fn oldest_generation(&self) -> u64 {
+        self.history
+            .front()
+            .map_or(self.board.generation, |frame| frame.generation)
+    }
+
+    // Passes the cells changed by one step, kept in `step`, to the events and the history.
+    fn finish_step(&mut self, generation: u64) {
+        let cells = mem::take(&mut self.step);
+        self.note_events(&cells);
+        self.record(generation, &cells, &[]);
+        self.step = cells;
+    }
+
+    fn record(&mut self, generation: u64, cells: &[(usize, C)], changes: &[(usize, C)]) {
+        if self.history_length > 0 {
+            self.history.push_back(Frame {
+                generation,
+                cells: cells.to_vec(),
+                changes: changes.to_vec(),
+            });
+            self.trim_history();
+        }
+    }
+
+    fn trim_history(&mut self) {
+        let oldest = self
+            .board
+            .generation
+            .saturating_sub(self.history_length as u64);
+        while self
+            .history
+            .front()
+            .is_some_and(|frame| frame.generation < oldest)
+        {
+            self.history.pop_front();
+        }
+    }
+
+    /// Brings back the board as it was at the beginning of `generation`, together with the changes
+    /// which were added to it then (see `add_change`), so that `next` will compute the same
+    /// generations again, unless something else is changed first. Everything after `generation`
+    /// is dropped from the history, and the changes added since then are lost.
+    /// Returns false, and changes nothing, if `generation` is in the future or no longer in
+    /// the history.
+    pub fn rewind_to(&mut self, generation: u64) -> bool {
+        if generation > self.board.generation || generation < self.oldest_generation() {
+            return false;
+        }
+
+        self.changes.clear();
+        self.events.clear();
+        while self
+            .history
+            .back()
+            .is_some_and(|frame| frame.generation >= generation)
+        {
+            let frame = self.history.pop_back().unwrap();
+            for &(id, cell) in &frame.cells {
+                if !self.events.contains(id) {
+                    self.events.put(id, self.board.map.get(id).unwrap());
+                }
+                self.board.map.put(id, cell);
+            }
+            // the frames are popped from the newest, so a change which is already there was
+            // applied later and wins
+            if frame.generation == generation {
+                for &(id, cell) in &frame.changes {
+                    if !self.changes.contains(id) {
+                        self.changes.put(id, cell);
+                    }
+                }
+            }
+        }
+        self.board.generation = generation;
+        // `back` has to be the same as `board` for active-cell tracking
+        self.refresh_active_cells();
+        self.restart_cycle_detection();
+        if let Some(statistics) = &mut self.statistics {
+            statistics.truncate(generation);
+        }
+        self.record_statistics(Duration::default());
+        self.notify_observers();
+        true
+    }
+
+    /// Goes back `n` generations, or as far as the history allows. Returns the number of
+    /// generations it went back.
+    pub fn step_back(&mut self, n: usize) -> usize {
+        let current = self.board.generation;
+        let generation = current
+            .saturating_sub(n as u64)
+            .max(self.oldest_generation());
+        self.rewind_to(generation);
+        (current - generation) as usize
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.board.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.board.height
+    }
+
+    #[inline]
+    pub fn board(&self) -> &Board<C> {
+        &self.board
     }
 
     #[inline]
     pub fn board_iter(&self) -> UMapIter<C> {
         self.board.map.iter()
     }
+
+    /// The number of generations computed so far.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.board.generation
+    }
+
+    /// Computes the next `n` generations.
+    pub fn step_n(&mut self, n: usize) {
+        (0..n).for_each(|_| self.next());
+    }
+
+    /// Computes the next generations as long as `predicate` is true for the current board.
+    /// Returns the number of generations computed.
+    pub fn run_while(&mut self, mut predicate: impl FnMut(&Board<C>) -> bool) -> usize {
+        let mut steps = 0;
+        while predicate(&self.board) {
+            self.next();
+            steps += 1;
+        }
+        steps
+    }
+
+    /// An endless iterator over the next generations, e.g.
+    /// `auto.generations().take(10).filter(...)`. Every call to its `next` computes
+    /// a new generation, like `Automaton::next`, and returns a copy of the board.
+    pub fn generations(&mut self) -> Generations<'_, C> {
+        Generations { automaton: self }
+    }
+
+    /// The board, its boundary, seed and generation, and the changes waiting for the next one.
+    /// The settings, like the schedule or the history, aren't saved.
+    pub fn snapshot(&self) -> Snapshot<C> {
+        Snapshot {
+            width: self.board.width,
+            height: self.board.height,
+            boundary: self.board.boundary,
+            seed: self.board.seed,
+            generation: self.board.generation,
+            cells: self.board.map.iter().map(|(_, cell)| *cell).collect(),
+            changes: self.changes.iter().map(|(_, cell)| *cell).collect(),
+        }
+    }
+
+    /// A new automaton, with the default settings, in the state saved in `snapshot`.
+    /// Fails if the cells don't fill the board, row by row.
+    pub fn from_snapshot(snapshot: Snapshot<C>) -> Result<Automaton<C>, SnapshotError> {
+        let (width, height) = (snapshot.width, snapshot.height);
+        if width == 0 || height == 0 || width.checked_mul(height) != Some(snapshot.cells.len()) {
+            return Err(SnapshotError::Invalid(format!(
+                "{} cells on a {}x{} board",
+                snapshot.cells.len(),
+                width,
+                height
+            )));
+        }
+        let mut map = UMap::<C>::with_capacity(width * height);
+        for (id, cell) in snapshot.cells.into_iter().enumerate() {
+            let expected = Pos2D::new((id % width) as i64, (id / width) as i64);
+            if cell.position() != expected {
+                return Err(SnapshotError::Invalid(format!(
+                    "the cell at {} is in the place of {}",
+                    cell.position(),
+                    expected
+                )));
+            }
+            map.put(id, cell);
+        }
+
+        let mut automaton = Automaton::from_board(Board {
+            width,
+            height,
+            boundary: snapshot.boundary,
+            map,
+            seed: snapshot.seed,
+            generation: snapshot.generation,
+        });
+        snapshot
+            .changes
+            .iter()
+            .for_each(|cell| automaton.add_change(cell));
+        Ok(automaton)
+    }
+}
+
+impl<C: AutomatonCell + Hash> Automaton<C> {
+    /// Turns on or off looking for still lifes and oscillators: from now on, every generation
+    /// computed by `next` is compared with the previous ones (see `CycleDetector`), and
+    /// `stability` tells if and how the board settled. The transient is counted from the current
+    /// generation, or from the last time the board was changed from the outside.
+    pub fn set_cycle_detection(&mut self, enabled: bool) {
+        self.cycles = if enabled {
+            Some(CycleDetector::new())
+        } else {
+            None
+        };
+        self.restart_cycle_detection();
+    }
+
+    /// Computes the next generations until the board settles, but no more than `max_generations`.
+    /// Returns `None` if it doesn't settle by then. Turns on cycle detection if it's off.
+    pub fn run_until_stable(&mut self, max_generations: usize) -> Option<Stability> {
+        if self.cycles.is_none() {
+            self.set_cycle_detection(true);
+        }
+        for _ in 0..max_generations {
+            if self.stability().is_some() {
+                break;
+            }
+            self.next();
+        }
+        self.stability()
+    }
+}
+
+/// The generations computed one by one by `Automaton::next`, as returned by
+/// `Automaton::generations`. Every board is a copy, and knows its generation.
+pub struct Generations<'a, C: AutomatonCell> {
+    automaton: &'a mut Automaton<C>,
 }
 
-impl<C: AutomatonCell> Iterator for Automaton<C> {
+impl<'a, C: AutomatonCell> Iterator for Generations<'a, C> {
     type Item = Board<C>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.board.update();
-        Some(self.board.clone())
+        self.automaton.next();
+        Some(self.automaton.board.clone())
     }
 }
@@ -0,0 +1,237 @@
+use crate::engine::boundary::Boundary;
+use crate::engine::grid::{self, Grid};
+use crate::fields::{Pos3D, MOORE3D, NEUMANN3D};
+use crate::utils::umap::UMap;
+
+use crate::utils::umap::UMapIter;
+
+use std::fmt::Debug;
+use std::mem;
+
+/// The 3D counterpart of `AutomatonCell`, for voxel worlds: caves, gas spreading through
+/// a building, multi-floor levels.
+pub trait AutomatonCell3D: Clone + Copy + PartialEq + Sized + Debug {
+    fn update(&self, neighborhood: &Neighborhood3D<Self>) -> Self;
+    fn position(&self) -> Pos3D;
+
+    fn new(pos: &Pos3D) -> Self;
+}
+
+pub trait Neighborhood3D<C: AutomatonCell3D> {
+    fn find_cell(&self, pos: &Pos3D) -> &C;
+
+    /// The six face neighbours of `pos`, in the order of `NEUMANN3D`.
+    fn neumann(&self, pos: &Pos3D) -> [&C; 6] {
+        std::array::from_fn(|i| self.find_cell(&(*pos + NEUMANN3D[i])))
+    }
+
+    /// All 26 voxels touching `pos`, in the order of `MOORE3D`.
+    fn moore(&self, pos: &Pos3D) -> [&C; 26] {
+        std::array::from_fn(|i| self.find_cell(&(*pos + MOORE3D[i])))
+    }
+}
+
+/// A `width` x `height` x `depth` board. Beyond its edges it behaves as its `Boundary` says,
+/// just like a `Board`.
+#[derive(Default, Clone)]
+pub struct Board3D<C: AutomatonCell3D> {
+    width: usize,
+    height: usize,
+    depth: usize,
+    boundary: Boundary<C>,
+    map: UMap<C>,
+}
+
+impl<C: AutomatonCell3D> Board3D<C> {
+    #[inline]
+    fn pos2id(width: usize, height: usize, pos: &Pos3D) -> usize {
+        (pos.z as usize * height + pos.y as usize) * width + pos.x as usize
+    }
+
+    #[inline]
+    fn id(&self, pos: &Pos3D) -> Option<usize> {
+        self.boundary
+            .resolve3d(self.width, self.height, self.depth, pos)
+            .map(|p| Board3D::<C>::pos2id(self.width, self.height, &p))
+    }
+
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Board3D::with_boundary(width, height, depth, Boundary::Torus)
+    }
+
+    pub fn with_boundary(width: usize, height: usize, depth: usize, boundary: Boundary<C>) -> Self {
+        let mut map = UMap::<C>::with_capacity(width * height * depth);
+        Pos3D::from_dims(width, height, depth)
+            .iter()
+            .for_each(|pos| map.put(Board3D::<C>::pos2id(width, height, pos), C::new(pos)));
+
+        Board3D {
+            width,
+            height,
+            depth,
+            boundary,
+            map,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[inline]
+    pub fn boundary(&self) -> &Boundary<C> {
+        &self.boundary
+    }
+
+    /// The cell at `pos`, after applying the boundary, or `None` if `pos` is behind a wall.
+    #[inline]
+    pub fn get(&self, pos: &Pos3D) -> Option<&C> {
+        self.id(pos).map(|id| self.map.get_ref(id).unwrap())
+    }
+
+    pub fn update(&self) -> Self {
+        let mut next = self.clone();
+        self.update_into(&mut next);
+        next
+    }
+
+    /// Computes the next generation into `target`, reusing its storage instead of allocating a new board.
+    pub fn update_into(&self, target: &mut Board3D<C>) {
+        target.width = self.width;
+        target.height = self.height;
+        target.depth = self.depth;
+        target.boundary = self.boundary;
+        grid::update_into(self, target);
+    }
+
+    pub fn copy_and_update(&self, cells: &[C]) -> Self {
+        grid::copy_and_update(self, cells)
+    }
+
+    pub fn copy_and_update_2(&self, cells: &UMap<C>) -> Self {
+        grid::copy_and_put(self, cells)
+    }
+}
+
+impl<C: AutomatonCell3D> Grid for Board3D<C> {
+    type Cell = C;
+    type Pos = Pos3D;
+
+    #[inline]
+    fn id(&self, pos: &Pos3D) -> Option<usize> {
+        Board3D::id(self, pos)
+    }
+
+    #[inline]
+    fn position(cell: &C) -> Pos3D {
+        cell.position()
+    }
+
+    #[inline]
+    fn cells(&self) -> &UMap<C> {
+        &self.map
+    }
+
+    #[inline]
+    fn cells_mut(&mut self) -> &mut UMap<C> {
+        &mut self.map
+    }
+
+    #[inline]
+    fn update_cell(&self, cell: &C) -> C {
+        cell.update(self)
+    }
+}
+
+impl<C: AutomatonCell3D> Neighborhood3D<C> for Board3D<C> {
+    fn find_cell(&self, pos: &Pos3D) -> &C {
+        match self.id(pos) {
+            Some(id) => self.map.get_ref(id).unwrap(),
+            None => self.boundary.wall().unwrap(),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct Automaton3D<C: AutomatonCell3D> {
+    board: Board3D<C>,
+    // the previous generation; the next one is computed into it and then the two are swapped
+    back: Board3D<C>,
+    changes: UMap<C>,
+}
+
+impl<C: AutomatonCell3D> Automaton3D<C> {
+    pub fn new(width: usize, height: usize, depth: usize) -> Automaton3D<C> {
+        Automaton3D::with_boundary(width, height, depth, Boundary::Torus)
+    }
+
+    pub fn with_boundary(
+        width: usize,
+        height: usize,
+        depth: usize,
+        boundary: Boundary<C>,
+    ) -> Automaton3D<C> {
+        let board = Board3D::<C>::with_boundary(width, height, depth, boundary);
+        Automaton3D {
+            back: board.clone(),
+            board,
+            changes: UMap::<C>::with_capacity(width * height * depth),
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.apply_changes();
+        self.board.update_into(&mut self.back);
+        mem::swap(&mut self.board, &mut self.back);
+    }
+
+    pub fn transform(&mut self, f: impl Fn(&Board3D<C>) -> Board3D<C>) {
+        self.board = f(&self.board);
+        // the next generation is computed into `back`, so it has to have the same size
+        self.back = self.board.clone();
+    }
+
+    pub fn add_change(&mut self, changed_cell: &C) {
+        grid::add_change(&self.board, &mut self.changes, changed_cell);
+    }
+
+    pub fn apply_changes(&mut self) {
+        grid::apply_changes(&mut self.board, &mut self.changes, |_, _| {});
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.board.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.board.height
+    }
+
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.board.depth
+    }
+
+    #[inline]
+    pub fn board(&self) -> &Board3D<C> {
+        &self.board
+    }
+
+    #[inline]
+    pub fn board_iter(&self) -> UMapIter<'_, C> {
+        self.board.map.iter()
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod automaton3d_tests {
+    use crate::engine::automaton3d::*;
+    use crate::engine::boundary::Boundary;
+    use crate::fields::Pos3D;
+
+    use spectral::prelude::*;
+
+    /// Gas fills a voxel if any of its neighbours is filled; `moore` decides which neighbours count.
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct Gas {
+        filled: bool,
+        moore: bool,
+        pos: Pos3D,
+    }
+
+    impl AutomatonCell3D for Gas {
+        fn update(&self, neighborhood: &Neighborhood3D<Self>) -> Self {
+            let spreads = if self.moore {
+                neighborhood.moore(&self.pos).iter().any(|c| c.filled)
+            } else {
+                neighborhood.neumann(&self.pos).iter().any(|c| c.filled)
+            };
+            Gas {
+                filled: self.filled || spreads,
+                ..*self
+            }
+        }
+
+        fn position(&self) -> Pos3D {
+            self.pos
+        }
+
+        fn new(pos: &Pos3D) -> Self {
+            Gas {
+                filled: false,
+                moore: false,
+                pos: *pos,
+            }
+        }
+    }
+
+    fn filled(auto: &Automaton3D<Gas>) -> usize {
+        auto.board_iter().filter(|(_, c)| c.filled).count()
+    }
+
+    #[test]
+    fn should_create_board3d() {
+        let auto = Automaton3D::<Gas>::new(4, 3, 2);
+        assert_that!(auto.width()).is_equal_to(4);
+        assert_that!(auto.height()).is_equal_to(3);
+        assert_that!(auto.depth()).is_equal_to(2);
+        assert_that!(auto.board_iter().count()).is_equal_to(24);
+    }
+
+    #[test]
+    fn should_find_neighbours_in_3d() {
+        let board = Board3D::<Gas>::new(5, 5, 5);
+        let pos = Pos3D::new(2, 2, 2);
+        let neumann = board.neumann(&pos);
+        assert_that!(neumann.iter().map(|c| c.pos).collect::<Vec<Pos3D>>()).is_equal_to(vec![
+            Pos3D::new(2, 1, 2),
+            Pos3D::new(3, 2, 2),
+            Pos3D::new(2, 3, 2),
+            Pos3D::new(1, 2, 2),
+            Pos3D::new(2, 2, 1),
+            Pos3D::new(2, 2, 3),
+        ]);
+        let moore = board.moore(&pos);
+        assert_that!(moore.iter().all(|c| c.pos != pos)).is_true();
+        assert_that!(moore[0].pos).is_equal_to(Pos3D::new(1, 1, 1));
+        assert_that!(moore[25].pos).is_equal_to(Pos3D::new(3, 3, 3));
+    }
+
+    #[test]
+    fn should_wrap_in_3d() {
+        let board = Board3D::<Gas>::new(4, 3, 2);
+        let moore = board.moore(&Pos3D::new(0, 0, 0));
+        assert_that!(moore[0].pos).is_equal_to(Pos3D::new(3, 2, 1));
+        assert_that!(board.find_cell(&Pos3D::new(4, -1, 2)).pos).is_equal_to(Pos3D::new(0, 2, 0));
+    }
+
+    #[test]
+    fn should_spread_gas() {
+        let mut neumann = Automaton3D::<Gas>::new(9, 9, 9);
+        neumann.add_change(&Gas {
+            filled: true,
+            moore: false,
+            pos: Pos3D::new(4, 4, 4),
+        });
+        neumann.next();
+        assert_that!(filled(&neumann)).is_equal_to(7);
+        neumann.next();
+        assert_that!(filled(&neumann)).is_equal_to(25);
+
+        let mut moore = Automaton3D::<Gas>::new(9, 9, 9);
+        moore.transform(|board| {
+            let cells: Vec<Gas> = Pos3D::from_dims(9, 9, 9)
+                .iter()
+                .map(|pos| Gas {
+                    filled: *pos == Pos3D::new(4, 4, 4),
+                    moore: true,
+                    pos: *pos,
+                })
+                .collect();
+            board.copy_and_update(&cells)
+        });
+        moore.next();
+        assert_that!(filled(&moore)).is_equal_to(27);
+        moore.next();
+        assert_that!(filled(&moore)).is_equal_to(125);
+    }
+
+    fn gas(x: i64, y: i64, z: i64) -> Gas {
+        Gas {
+            filled: true,
+            moore: false,
+            pos: Pos3D::new(x, y, z),
+        }
+    }
+
+    #[test]
+    fn should_read_the_boundary_in_3d() {
+        let wall = Gas::new(&Pos3D::new(-1, -1, -1));
+        let board = Board3D::<Gas>::with_boundary(4, 3, 2, Boundary::Wall(wall));
+        assert_that!(board.find_cell(&Pos3D::new(0, 0, -1))).is_equal_to(&wall);
+        assert_that!(board.get(&Pos3D::new(4, 0, 0))).is_none();
+        assert_that!(board.get(&Pos3D::new(3, 2, 1)).map(|c| c.pos))
+            .is_equal_to(Some(Pos3D::new(3, 2, 1)));
+
+        let board = Board3D::<Gas>::with_boundary(4, 3, 2, Boundary::Reflective);
+        let neumann = board.neumann(&Pos3D::new(0, 0, 0));
+        assert_that!(neumann[0].pos).is_equal_to(Pos3D::new(0, 0, 0));
+        assert_that!(neumann[4].pos).is_equal_to(Pos3D::new(0, 0, 0));
+    }
+
+    #[test]
+    fn should_not_spread_gas_through_walls() {
+        let mut auto = Automaton3D::<Gas>::with_boundary(
+            5,
+            5,
+            5,
+            Boundary::Wall(Gas::new(&Pos3D::new(0, 0, 0))),
+        );
+        auto.add_change(&gas(0, 0, 0));
+        // behind the wall, so it's ignored
+        auto.add_change(&gas(-1, 0, 0));
+        auto.next();
+        assert_that!(filled(&auto)).is_equal_to(4);
+
+        let mut torus = Automaton3D::<Gas>::new(5, 5, 5);
+        torus.add_change(&gas(0, 0, 0));
+        torus.add_change(&gas(-1, 0, 0));
+        torus.next();
+        assert_that!(filled(&torus)).is_equal_to(12);
+    }
+
+    #[test]
+    fn should_apply_changes_again() {
+        let mut auto = Automaton3D::<Gas>::new(4, 4, 4);
+        auto.add_change(&gas(1, 1, 1));
+        auto.apply_changes();
+        assert_that!(filled(&auto)).is_equal_to(1);
+        auto.apply_changes();
+        auto.add_change(&gas(2, 2, 2));
+        auto.apply_changes();
+        assert_that!(filled(&auto)).is_equal_to(2);
+        assert_that!(auto.board().get(&Pos3D::new(2, 2, 2)).map(|c| c.filled))
+            .is_equal_to(Some(true));
+    }
+}
@@ -0,0 +1,740 @@
+#[cfg(test)]
+mod automaton_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::boundary::Boundary;
+    use crate::engine::test_helpers::blinker;
+    use crate::examples::forest_fire::{ForestFire, Tree};
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::examples::hex_life::HexLife;
+    use crate::examples::langtons_ant::LangtonsAnt;
+    use crate::fields::{Dir2D, Dir6, Pos2D, DIRS6, DIRS8};
+    use crate::utils::uset::USet;
+
+    use rand::{Rng, SeedableRng, XorShiftRng};
+    use spectral::prelude::*;
+
+    fn alive(auto: &Automaton<GameOfLife>) -> Vec<Pos2D> {
+        auto.board_iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect()
+    }
+
+    #[test]
+    fn should_create_rectangular_board() {
+        let board = Board::<GameOfLife>::new(7, 3);
+        assert_that!(board.width()).is_equal_to(7);
+        assert_that!(board.height()).is_equal_to(3);
+
+        let auto = Automaton::<GameOfLife>::new(7, 3);
+        assert_that!(auto.board_iter().count()).is_equal_to(21);
+        assert_that!(auto.board_iter().all(|(_, c)| !c.life)).is_true();
+    }
+
+    #[test]
+    fn should_find_cells_on_rectangular_board() {
+        let board = Board::<GameOfLife>::new(7, 3);
+        assert_that!(board.find_cell(&Pos2D::new(6, 2)).pos).is_equal_to(Pos2D::new(6, 2));
+        assert_that!(board.find_cell(&Pos2D::new(7, 0)).pos).is_equal_to(Pos2D::new(0, 0));
+        assert_that!(board.find_cell(&Pos2D::new(0, 3)).pos).is_equal_to(Pos2D::new(0, 0));
+        assert_that!(board.find_cell(&Pos2D::new(-1, -1)).pos).is_equal_to(Pos2D::new(6, 2));
+    }
+
+    #[test]
+    fn should_wrap_blinker_on_rectangular_board() {
+        let mut auto = Automaton::<GameOfLife>::new(8, 4);
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(7, 1)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(0, 1)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(1, 1)));
+
+        auto.next();
+        let mut vertical = alive(&auto);
+        vertical.sort_by_key(|p| (p.y, p.x));
+        assert_that!(vertical).is_equal_to(vec![
+            Pos2D::new(0, 0),
+            Pos2D::new(0, 1),
+            Pos2D::new(0, 2),
+        ]);
+
+        auto.next();
+        assert_that!(alive(&auto).len()).is_equal_to(3);
+        assert_that!(auto
+            .board_iter()
+            .filter(|(_, c)| c.life)
+            .all(|(_, c)| c.pos.y == 1))
+        .is_true();
+    }
+
+    #[test]
+    fn should_not_wrap_blinker_behind_wall() {
+        let dead = GameOfLife::new(&Pos2D::new(-1, -1));
+        let mut auto = Automaton::<GameOfLife>::with_boundary(8, 4, Boundary::Wall(dead));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(0, 0)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(1, 0)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(2, 0)));
+
+        auto.next();
+        assert_that!(alive(&auto)).is_equal_to(vec![Pos2D::new(1, 0), Pos2D::new(1, 1)]);
+
+        auto.next();
+        assert_that!(alive(&auto).is_empty()).is_true();
+    }
+
+    #[test]
+    fn should_read_the_wall_cell_beyond_the_edges() {
+        let wall = GameOfLife::new_life(&Pos2D::new(-1, -1));
+        let board = Board::<GameOfLife>::with_boundary(4, 4, Boundary::Wall(wall));
+        assert_that!(board.find_cell(&Pos2D::new(-1, 2))).is_equal_to(&wall);
+        let corner = board.moore(&Pos2D::new(0, 0));
+        assert_that!(corner.values().filter(|c| c.life).count()).is_equal_to(5);
+        let middle = board.moore(&Pos2D::new(1, 1));
+        assert_that!(middle.values().filter(|c| c.life).count()).is_equal_to(0);
+    }
+
+    #[test]
+    fn should_keep_still_life_in_reflective_corner() {
+        // a single cell in the corner and its mirror images make a block
+        let mut auto = Automaton::<GameOfLife>::with_boundary(6, 6, Boundary::Reflective);
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(0, 0)));
+        auto.next();
+        assert_that!(alive(&auto)).is_equal_to(vec![Pos2D::new(0, 0)]);
+    }
+
+    #[test]
+    fn should_find_hex_neighbours() {
+        let mut board = Board::<HexLife>::new(6, 6);
+        board = board.copy_and_update(&[
+            HexLife::new_life(&Pos2D::new(2, 0)),
+            HexLife::new_life(&Pos2D::new(3, 1)),
+            HexLife::new_life(&Pos2D::new(1, 1)),
+            HexLife::new_life(&Pos2D::new(3, 2)),
+        ]);
+        let hood = board.hex(&Pos2D::new(2, 1));
+        assert_that!(hood.len()).is_equal_to(6);
+        assert_that!(hood[Dir6::UpLeft].life).is_true();
+        assert_that!(hood[Dir6::Right].life).is_true();
+        assert_that!(hood[Dir6::Left].life).is_true();
+        assert_that!(hood[Dir6::DownRight].life).is_true();
+        assert_that!(hood[Dir6::UpRight].life).is_false();
+        assert_that!(hood[Dir6::DownLeft].life).is_false();
+        let dirs: Vec<Dir6> = hood.iter().map(|(dir, _)| dir).collect();
+        assert_that!(dirs).is_equal_to(DIRS6.to_vec());
+        assert_that!(hood.get(Dir6::UpLeft).map(|c| c.pos)).is_equal_to(Some(Pos2D::new(2, 0)));
+    }
+
+    #[test]
+    fn should_wrap_hex_neighbours() {
+        let board = Board::<HexLife>::new(4, 4);
+        let hood = board.hex(&Pos2D::new(0, 0));
+        assert_that!(hood[Dir6::UpLeft].pos).is_equal_to(Pos2D::new(3, 3));
+        assert_that!(hood[Dir6::UpRight].pos).is_equal_to(Pos2D::new(0, 3));
+        assert_that!(hood[Dir6::Left].pos).is_equal_to(Pos2D::new(3, 0));
+    }
+
+    #[test]
+    fn should_find_neighbours_within_radius() {
+        let board = Board::<GameOfLife>::new(10, 10);
+        fn positions<'a>(cells: impl Iterator<Item = &'a GameOfLife>) -> Vec<Pos2D> {
+            cells.map(|c| c.pos).collect()
+        }
+
+        let square = positions(board.moore_radius(&Pos2D::new(5, 5), 2));
+        assert_that!(square.len()).is_equal_to(24);
+        assert_that!(square[0]).is_equal_to(Pos2D::new(3, 3));
+        assert_that!(square[23]).is_equal_to(Pos2D::new(7, 7));
+        assert_that!(square.contains(&Pos2D::new(5, 5))).is_false();
+
+        let diamond = positions(board.neumann_radius(&Pos2D::new(5, 5), 2));
+        assert_that!(diamond).is_equal_to(vec![
+            Pos2D::new(5, 3),
+            Pos2D::new(4, 4),
+            Pos2D::new(5, 4),
+            Pos2D::new(6, 4),
+            Pos2D::new(3, 5),
+            Pos2D::new(4, 5),
+            Pos2D::new(6, 5),
+            Pos2D::new(7, 5),
+            Pos2D::new(4, 6),
+            Pos2D::new(5, 6),
+            Pos2D::new(6, 6),
+            Pos2D::new(5, 7),
+        ]);
+
+        let wrapped = positions(board.moore_radius(&Pos2D::new(0, 0), 1));
+        assert_that!(wrapped[0]).is_equal_to(Pos2D::new(9, 9));
+        assert_that!(board.moore_radius(&Pos2D::new(0, 0), 1).count())
+            .is_equal_to(board.moore(&Pos2D::new(0, 0)).len());
+        assert_that!(board.neumann_radius(&Pos2D::new(0, 0), 0).count()).is_equal_to(0);
+    }
+
+    fn cells<C: AutomatonCell>(auto: &Automaton<C>) -> Vec<C> {
+        auto.board_iter().map(|(_, c)| *c).collect()
+    }
+
+    fn compare_serial_and_parallel<C: AutomatonCell>(
+        seed: &[C],
+        width: usize,
+        height: usize,
+        threads: usize,
+    ) {
+        let mut serial = Automaton::<C>::new(width, height);
+        let mut parallel = Automaton::<C>::new(width, height);
+        parallel.set_update_mode(UpdateMode::Parallel(threads));
+        seed.iter().for_each(|c| {
+            serial.add_change(c);
+            parallel.add_change(c);
+        });
+
+        for _ in 0..50 {
+            serial.next();
+            parallel.next();
+            assert_that!(cells(&parallel)).is_equal_to(cells(&serial));
+        }
+    }
+
+    #[test]
+    fn should_update_game_of_life_in_parallel() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let soup: Vec<GameOfLife> = Pos2D::from_dims(30, 20)
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .map(GameOfLife::new_life)
+            .collect();
+
+        compare_serial_and_parallel(&soup, 30, 20, 4);
+        compare_serial_and_parallel(&soup, 30, 20, 7);
+        compare_serial_and_parallel(&soup, 30, 20, 64);
+        compare_serial_and_parallel(&soup, 30, 20, 0);
+        let empty = Board::<GameOfLife>::new(0, 0).update_parallel(0);
+        assert_that!(empty.generation()).is_equal_to(1);
+    }
+
+    #[test]
+    fn should_update_langtons_ants_in_parallel() {
+        let ants = vec![
+            LangtonsAnt::new_ant(&Pos2D::new(5, 5)),
+            LangtonsAnt::new_ant(&Pos2D::new(20, 9)),
+            LangtonsAnt::new_ant(&Pos2D::new(11, 17)),
+        ];
+
+        compare_serial_and_parallel(&ants, 32, 24, 3);
+        compare_serial_and_parallel(&ants, 32, 24, 5);
+    }
+
+    #[test]
+    fn should_list_neighbours_in_canonical_order() {
+        let board = Board::<GameOfLife>::new(5, 5);
+        let pos = Pos2D::new(2, 2);
+
+        let moore = board.moore(&pos);
+        let dirs: Vec<Dir2D> = moore.iter().map(|(dir, _)| dir).collect();
+        assert_that!(dirs).is_equal_to(DIRS8.to_vec());
+        for (dir, cell) in moore.iter() {
+            assert_that!(cell.pos).is_equal_to(pos.move_by_one(dir));
+            assert_that!(moore[dir].pos).is_equal_to(cell.pos);
+        }
+
+        let neumann = board.neumann(&pos);
+        assert_that!(neumann.len()).is_equal_to(4);
+        assert_that!(neumann[Dir2D::Left].pos).is_equal_to(Pos2D::new(1, 2));
+        assert_that!(neumann.get(Dir2D::Down).map(|c| c.pos)).is_equal_to(Some(Pos2D::new(2, 3)));
+        assert_that!(neumann.get(Dir2D::UpLeft).is_none()).is_true();
+    }
+
+    #[test]
+    fn should_apply_changes_in_place() {
+        let mut auto = Automaton::<GameOfLife>::new(5, 5);
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(1, 1)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(3, 4)));
+        auto.apply_changes();
+        assert_that!(alive(&auto)).is_equal_to(vec![Pos2D::new(1, 1), Pos2D::new(3, 4)]);
+        auto.apply_changes();
+        assert_that!(alive(&auto).len()).is_equal_to(2);
+    }
+
+    fn compare_full_and_tracked<C: AutomatonCell>(
+        seed: &[C],
+        later: &[C],
+        width: usize,
+        height: usize,
+        boundary: Boundary<C>,
+    ) {
+        let mut full = Automaton::<C>::with_boundary(width, height, boundary);
+        let mut tracked = Automaton::<C>::with_boundary(width, height, boundary);
+        seed.iter().for_each(|c| {
+            full.add_change(c);
+            tracked.add_change(c);
+        });
+        full.apply_changes();
+        tracked.apply_changes();
+        tracked.set_active_tracking(true);
+
+        for i in 0..50 {
+            if i == 20 {
+                later.iter().for_each(|c| {
+                    full.add_change(c);
+                    tracked.add_change(c);
+                });
+            }
+            full.next();
+            tracked.next();
+            assert_that!(cells(&tracked)).is_equal_to(cells(&full));
+        }
+    }
+
+    #[test]
+    fn should_track_active_game_of_life_cells() {
+        let mut rng = XorShiftRng::from_seed([4, 3, 2, 1]);
+        let soup: Vec<GameOfLife> = Pos2D::from_range(Pos2D::new(0, 0), Pos2D::new(8, 8))
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .map(GameOfLife::new_life)
+            .collect();
+        let glider: Vec<GameOfLife> = vec![(21, 20), (22, 21), (20, 22), (21, 22), (22, 22)]
+            .into_iter()
+            .map(|(x, y)| GameOfLife::new_life(&Pos2D::new(x, y)))
+            .collect();
+
+        compare_full_and_tracked(&soup, &glider, 30, 30, Boundary::Torus);
+        compare_full_and_tracked(&soup, &glider, 30, 30, Boundary::Reflective);
+        compare_full_and_tracked(&soup, &glider, 30, 30, Boundary::KleinBottle);
+        let dead = GameOfLife::new(&Pos2D::new(-1, -1));
+        compare_full_and_tracked(&soup, &glider, 30, 30, Boundary::Wall(dead));
+    }
+
+    #[test]
+    fn should_track_active_langtons_ants() {
+        let ants = vec![
+            LangtonsAnt::new_ant(&Pos2D::new(5, 5)),
+            LangtonsAnt::new_ant(&Pos2D::new(20, 9)),
+        ];
+        let later = vec![LangtonsAnt::new_ant(&Pos2D::new(11, 17))];
+
+        compare_full_and_tracked(&ants, &later, 32, 24, Boundary::Torus);
+    }
+
+    #[test]
+    fn should_update_only_around_changes() {
+        let mut auto = Automaton::<GameOfLife>::new(20, 20);
+        assert_that!(auto.active_cells().is_none()).is_true();
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(9, 10)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(10, 10)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(11, 10)));
+        auto.apply_changes();
+
+        auto.set_active_tracking(true);
+        assert_that!(auto.active_cells().map(USet::len)).is_equal_to(Some(15));
+
+        // two cells die, two are born, and all four have 3x3 neighbourhoods
+        auto.next();
+        assert_that!(auto.active_cells().map(USet::len)).is_equal_to(Some(21));
+        auto.next();
+        assert_that!(auto.active_cells().map(USet::len)).is_equal_to(Some(21));
+
+        auto.set_active_tracking(false);
+        assert_that!(auto.active_cells().is_none()).is_true();
+    }
+
+    // A cell which turns on if it's already on, or if its left neighbour is.
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct Spread {
+        on: bool,
+        pos: Pos2D,
+    }
+
+    impl AutomatonCell for Spread {
+        fn update(&self, neighborhood: &Neighborhood<Self>) -> Self {
+            let left = neighborhood.find_cell(&self.pos.move_by_one(Dir2D::Left));
+            Spread {
+                on: self.on || left.on,
+                pos: self.pos,
+            }
+        }
+
+        fn position(&self) -> Pos2D {
+            self.pos
+        }
+
+        fn new(pos: &Pos2D) -> Self {
+            Spread {
+                on: false,
+                pos: *pos,
+            }
+        }
+    }
+
+    fn spread_after_one_generation(schedule: Schedule) -> usize {
+        let mut auto = Automaton::<Spread>::new(6, 2);
+        auto.set_schedule(schedule);
+        auto.add_change(&Spread {
+            on: true,
+            pos: Pos2D::new(0, 0),
+        });
+        auto.next();
+        auto.board_iter().filter(|(_, c)| c.on).count()
+    }
+
+    #[test]
+    fn should_see_updated_neighbours_in_sweep() {
+        assert_that!(spread_after_one_generation(Schedule::Synchronous)).is_equal_to(2);
+        assert_that!(spread_after_one_generation(Schedule::Sweep)).is_equal_to(6);
+        // in a random order, some cells are updated after their left neighbours, some before
+        let random = spread_after_one_generation(Schedule::RandomSequential);
+        assert_that!((2..=6).contains(&random)).is_true();
+    }
+
+    fn run_soup(schedule: Schedule, seed: u64) -> Vec<GameOfLife> {
+        let mut rng = XorShiftRng::from_seed([3, 1, 4, 1]);
+        let mut auto = Automaton::<GameOfLife>::new(20, 20);
+        auto.set_schedule(schedule);
+        auto.set_seed(seed);
+        Pos2D::from_dims(20, 20)
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .for_each(|p| auto.add_change(&GameOfLife::new_life(p)));
+        (0..10).for_each(|_| auto.next());
+        assert_that!(auto.board().generation()).is_equal_to(10);
+        cells(&auto)
+    }
+
+    #[test]
+    fn should_replay_random_schedules_with_the_same_seed() {
+        for &schedule in &[Schedule::RandomSequential, Schedule::RandomIndependent(0.5)] {
+            assert_that!(run_soup(schedule, 7)).is_equal_to(run_soup(schedule, 7));
+            assert_that!(run_soup(schedule, 7)).is_not_equal_to(run_soup(schedule, 8));
+            assert_that!(run_soup(schedule, 7)).is_not_equal_to(run_soup(Schedule::Synchronous, 7));
+        }
+    }
+
+    #[test]
+    fn should_update_cells_with_probability() {
+        let start = run_soup(Schedule::RandomIndependent(0.0), 1);
+        let mut rng = XorShiftRng::from_seed([3, 1, 4, 1]);
+        let alive = Pos2D::from_dims(20, 20)
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .count();
+        assert_that!(start.iter().filter(|c| c.life).count()).is_equal_to(alive);
+
+        assert_that!(run_soup(Schedule::RandomIndependent(1.0), 1))
+            .is_equal_to(run_soup(Schedule::Synchronous, 1));
+    }
+
+    #[test]
+    fn should_keep_tracking_active_cells_with_other_schedules() {
+        let mut auto = Automaton::<GameOfLife>::new(10, 10);
+        auto.set_schedule(Schedule::Sweep);
+        auto.set_active_tracking(true);
+        for &(x, y) in &[(1, 1), (2, 1), (1, 2), (2, 2)] {
+            auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, y)));
+        }
+        auto.next();
+        assert_that!(alive(&auto).is_empty()).is_false();
+
+        let mut fresh = Automaton::from_board(auto.board().clone());
+        fresh.set_active_tracking(true);
+        assert_that!(auto.active_cells().map(USet::len))
+            .is_equal_to(fresh.active_cells().map(USet::len));
+    }
+
+    fn run_forest(seed: u64, mode: UpdateMode) -> Vec<ForestFire> {
+        let mut auto = Automaton::<ForestFire>::new(40, 30);
+        auto.set_seed(seed);
+        auto.set_update_mode(mode);
+        (0..100).for_each(|_| auto.next());
+        cells(&auto)
+    }
+
+    #[test]
+    fn should_replay_randomness_inside_cells() {
+        let forest = run_forest(5, UpdateMode::Serial);
+        assert_that!(forest.iter().any(|c| c.tree == Tree::Growing)).is_true();
+        assert_that!(run_forest(5, UpdateMode::Parallel(4))).is_equal_to(&forest);
+        assert_that!(run_forest(6, UpdateMode::Serial)).is_not_equal_to(&forest);
+    }
+
+    #[test]
+    fn should_give_each_cell_its_own_random_numbers() {
+        let mut auto = Automaton::<GameOfLife>::new(4, 4);
+        let (p1, p2) = (Pos2D::new(1, 2), Pos2D::new(2, 1));
+        let first: u64 = auto.board().rng(&p1).gen();
+        assert_that!(auto.board().rng(&p1).gen::<u64>()).is_equal_to(first);
+        assert_that!(auto.board().rng(&p2).gen::<u64>()).is_not_equal_to(first);
+
+        auto.next();
+        assert_that!(auto.board().generation()).is_equal_to(1);
+        assert_that!(auto.board().rng(&p1).gen::<u64>()).is_not_equal_to(first);
+
+        let x = auto.board().pseudo_normal_rng(&p1, 2.0).gen::<f64>();
+        assert_that!((0.0..=1.0).contains(&x)).is_true();
+    }
+
+    fn glider(auto: &mut Automaton<GameOfLife>) {
+        for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, y)));
+        }
+    }
+
+    fn replay_with_history(schedule: Schedule, tracking: bool) {
+        let mut auto = Automaton::<GameOfLife>::new(12, 12);
+        auto.set_schedule(schedule);
+        auto.set_active_tracking(tracking);
+        auto.set_history_length(10);
+        glider(&mut auto);
+
+        let mut boards = Vec::new();
+        for _ in 0..6 {
+            auto.next();
+            boards.push(cells(&auto));
+        }
+        assert_that!(auto.step_back(2)).is_equal_to(2);
+        assert_that!(auto.board().generation()).is_equal_to(4);
+        assert_that!(cells(&auto)).is_equal_to(&boards[3]);
+
+        assert_that!(auto.rewind_to(1)).is_true();
+        assert_that!(cells(&auto)).is_equal_to(&boards[0]);
+        // and forward again, to the same generations
+        for board in &boards[1..] {
+            auto.next();
+            assert_that!(cells(&auto)).is_equal_to(board);
+        }
+    }
+
+    #[test]
+    fn should_step_back_and_forward_again() {
+        replay_with_history(Schedule::Synchronous, false);
+        replay_with_history(Schedule::Synchronous, true);
+        replay_with_history(Schedule::Sweep, false);
+        replay_with_history(Schedule::RandomSequential, true);
+    }
+
+    #[test]
+    fn should_rewind_to_pending_changes() {
+        let mut auto = Automaton::<GameOfLife>::new(12, 12);
+        auto.set_history_length(5);
+        glider(&mut auto);
+        auto.next();
+        let first = cells(&auto);
+
+        assert_that!(auto.rewind_to(0)).is_true();
+        assert_that!(alive(&auto).is_empty()).is_true();
+        // the glider is still waiting to be added
+        auto.next();
+        assert_that!(cells(&auto)).is_equal_to(&first);
+    }
+
+    #[test]
+    fn should_rewind_to_changes_applied_in_several_batches() {
+        let mut auto = Automaton::<GameOfLife>::new(12, 12);
+        auto.set_history_length(5);
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(1, 1)));
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(9, 9)));
+        auto.apply_changes();
+        // overrides the cell from the first batch
+        auto.add_change(&GameOfLife::new(&Pos2D::new(9, 9)));
+        for x in 5..8 {
+            auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, 6)));
+        }
+        auto.next();
+        let first = cells(&auto);
+        assert_that!(alive(&auto).len()).is_equal_to(3);
+
+        assert_that!(auto.rewind_to(0)).is_true();
+        assert_that!(alive(&auto).is_empty()).is_true();
+        auto.next();
+        assert_that!(cells(&auto)).is_equal_to(&first);
+    }
+
+    #[test]
+    fn should_branch_after_rewinding() {
+        let mut auto = Automaton::<GameOfLife>::new(12, 12);
+        auto.set_history_length(5);
+        glider(&mut auto);
+        (0..3).for_each(|_| auto.next());
+        auto.step_back(2);
+
+        let block = [(8, 8), (9, 8), (8, 9), (9, 9)];
+        for &(x, y) in &block {
+            auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, y)));
+        }
+        (0..2).for_each(|_| auto.next());
+        assert_that!(auto.board().generation()).is_equal_to(3);
+        assert_that!(alive(&auto).len()).is_equal_to(9);
+
+        // the history follows the new branch
+        auto.step_back(2);
+        assert_that!(alive(&auto).len()).is_equal_to(5);
+        auto.next();
+        assert_that!(alive(&auto).len()).is_equal_to(9);
+    }
+
+    #[test]
+    fn should_keep_only_the_configured_history() {
+        let mut auto = Automaton::<GameOfLife>::new(12, 12);
+        glider(&mut auto);
+        auto.next();
+        assert_that!(auto.step_back(1)).is_equal_to(0);
+
+        auto.set_history_length(3);
+        (0..10).for_each(|_| auto.next());
+        assert_that!(auto.oldest_generation()).is_equal_to(8);
+        assert_that!(auto.rewind_to(7)).is_false();
+        assert_that!(auto.rewind_to(12)).is_false();
+        assert_that!(auto.board().generation()).is_equal_to(11);
+        assert_that!(auto.step_back(5)).is_equal_to(3);
+
+        auto.set_history_length(0);
+        assert_that!(auto.oldest_generation()).is_equal_to(8);
+    }
+
+    #[test]
+    fn should_undo_transformations() {
+        let mut auto = Automaton::<GameOfLife>::new(6, 6);
+        auto.set_history_length(2);
+        glider(&mut auto);
+        auto.next();
+        let before = cells(&auto);
+        auto.transform(|board| board.copy_and_update_one(&GameOfLife::new_life(&Pos2D::new(5, 5))));
+        assert_that!(cells(&auto)).is_not_equal_to(&before);
+
+        auto.next();
+        auto.rewind_to(1);
+        assert_that!(cells(&auto)).is_equal_to(&before);
+    }
+
+    fn alive_on(board: &Board<GameOfLife>) -> Vec<Pos2D> {
+        board
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect()
+    }
+
+    #[test]
+    fn should_iterate_over_generations() {
+        let mut auto = blinker(5);
+        let horizontal = vec![Pos2D::new(1, 2), Pos2D::new(2, 2), Pos2D::new(3, 2)];
+        let vertical = vec![Pos2D::new(2, 1), Pos2D::new(2, 2), Pos2D::new(2, 3)];
+
+        let boards: Vec<Board<GameOfLife>> = auto.generations().take(4).collect();
+        let generations: Vec<u64> = boards.iter().map(Board::generation).collect();
+        assert_that!(generations).is_equal_to(vec![1, 2, 3, 4]);
+        assert_that!(alive_on(&boards[0])).is_equal_to(&vertical);
+        assert_that!(alive_on(&boards[1])).is_equal_to(&horizontal);
+        assert_that!(alive_on(&boards[2])).is_equal_to(&vertical);
+
+        // the automaton is at the last generation returned
+        assert_that!(auto.generation()).is_equal_to(4);
+        assert_that!(alive(&auto)).is_equal_to(&horizontal);
+    }
+
+    #[test]
+    fn should_step_n_generations() {
+        let mut auto = blinker(5);
+        auto.step_n(0);
+        assert_that!(auto.generation()).is_equal_to(0);
+        auto.step_n(3);
+        assert_that!(auto.generation()).is_equal_to(3);
+        assert_that!(alive(&auto)).is_equal_to(vec![
+            Pos2D::new(2, 1),
+            Pos2D::new(2, 2),
+            Pos2D::new(2, 3),
+        ]);
+    }
+
+    #[test]
+    fn should_run_while_predicate_holds() {
+        let mut auto = Automaton::<GameOfLife>::new(12, 12);
+        glider(&mut auto);
+        auto.apply_changes();
+        // the glider moves by one cell down and right every four generations
+        let steps = auto.run_while(|board| !board.find_cell(&Pos2D::new(4, 4)).life);
+        assert_that!(steps).is_equal_to(8);
+        assert_that!(auto.generation()).is_equal_to(8);
+        assert_that!(alive(&auto)).is_equal_to(vec![
+            Pos2D::new(3, 2),
+            Pos2D::new(4, 3),
+            Pos2D::new(2, 4),
+            Pos2D::new(3, 4),
+            Pos2D::new(4, 4),
+        ]);
+
+        assert_that!(auto.run_while(|_| false)).is_equal_to(0);
+    }
+
+    #[test]
+    fn should_compare_boards() {
+        let board = Board::<GameOfLife>::new(5, 5);
+        assert_that!(board.clone()).is_equal_to(&board);
+        assert_that!(Board::<GameOfLife>::new(5, 4)).is_not_equal_to(&board);
+
+        let changed = board.copy_and_update_one(&GameOfLife::new_life(&Pos2D::new(1, 1)));
+        assert_that!(changed).is_not_equal_to(&board);
+        assert_that!(Board::with_boundary(5, 5, Boundary::Reflective)).is_not_equal_to(&board);
+
+        // the generation and the seed don't count
+        assert_that!(board.update()).is_equal_to(&board);
+        let mut auto = Automaton::from_board(board.clone());
+        auto.set_seed(7);
+        assert_that!(auto.board()).is_equal_to(&board);
+    }
+
+    #[test]
+    fn should_diff_boards() {
+        let mut auto = blinker(5);
+        auto.apply_changes();
+        let old = auto.board().clone();
+        auto.next();
+        let new = auto.board().clone();
+
+        let diff = old.diff(&new);
+        assert_that!(diff.len()).is_equal_to(4);
+        assert_that!(diff.generations()).is_equal_to((0, 1));
+        let cells: Vec<(Pos2D, bool, bool)> = diff
+            .iter()
+            .map(|(pos, old, new)| (pos, old.life, new.life))
+            .collect();
+        assert_that!(cells).is_equal_to(vec![
+            (Pos2D::new(2, 1), false, true),
+            (Pos2D::new(1, 2), true, false),
+            (Pos2D::new(3, 2), true, false),
+            (Pos2D::new(2, 3), false, true),
+        ]);
+
+        assert_that!(old.diff(&old).is_empty()).is_true();
+        assert_that!(new.diff(&old).positions()).is_equal_to(diff.positions());
+    }
+
+    #[test]
+    fn should_replay_boards_from_diffs() {
+        let mut auto = Automaton::<GameOfLife>::new(12, 12);
+        glider(&mut auto);
+        auto.apply_changes();
+        let start = auto.board().clone();
+        let boards: Vec<Board<GameOfLife>> = auto.generations().take(10).collect();
+
+        let mut diffs = Vec::new();
+        let mut previous = &start;
+        for board in &boards {
+            diffs.push(previous.diff(board));
+            previous = board;
+        }
+
+        let mut replay = start.clone();
+        for (diff, board) in diffs.iter().zip(boards.iter()) {
+            replay = diff.apply(&replay);
+            assert_that!(replay).is_equal_to(board);
+            assert_that!(replay.generation()).is_equal_to(board.generation());
+        }
+        for diff in diffs.iter().rev() {
+            replay = diff.revert(&replay);
+        }
+        assert_that!(replay).is_equal_to(&start);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_not_diff_boards_of_different_sizes() {
+        Board::<GameOfLife>::new(5, 5).diff(&Board::<GameOfLife>::new(5, 6));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::fields::{Pos2D, Pos3D};
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+/// Decides what a board looks like beyond its edges.
+///
+/// `Board::find_cell` (and so `Neighborhood::neumann` and `Neighborhood::moore`) asks the boundary
+/// where a position outside the board should be read from. The default is `Torus`, which is how
+/// boards behaved before boundaries were configurable. `Board3D` uses it in the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum Boundary<C> {
+    /// Opposite edges are glued together: a glider leaving the board on the right comes back on the left.
+    #[default]
+    Torus,
+    /// Everything beyond the edges reads as the given constant cell, eg. a dead cell or a wall.
+    Wall(C),
+    /// The board is mirrored at its edges: the cell just outside an edge is the one just inside it.
+    Reflective,
+    /// Left and right edges are glued as in `Torus`, but crossing the top or bottom edge also
+    /// mirrors the board horizontally. In 3D the front and back faces are glued as in `Torus`.
+    KleinBottle,
+}
+
+#[inline]
+fn wrap(i: i64, dim: usize) -> i64 {
+    i.rem_euclid(dim as i64)
+}
+
+#[inline]
+fn reflect(i: i64, dim: usize) -> i64 {
+    let dim = dim as i64;
+    match i.rem_euclid(2 * dim) {
+        r if r < dim => r,
+        r => 2 * dim - 1 - r,
+    }
+}
+
+impl<C> Boundary<C> {
+    /// Maps `pos` to the position on a `width` x `height` board it should be read from.
+    /// Positions already on the board are left as they are. Returns `None` if `pos` lies
+    /// outside of a walled board.
+    pub fn resolve(&self, width: usize, height: usize, pos: &Pos2D) -> Option<Pos2D> {
+        self.resolve_coords([width, height], [pos.x, pos.y])
+            .map(|[x, y]| Pos2D::new(x, y))
+    }
+
+    /// Like `resolve`, but on a `width` x `height` x `depth` board.
+    pub fn resolve3d(
+        &self,
+        width: usize,
+        height: usize,
+        depth: usize,
+        pos: &Pos3D,
+    ) -> Option<Pos3D> {
+        self.resolve_coords([width, height, depth], [pos.x, pos.y, pos.z])
+            .map(|[x, y, z]| Pos3D::new(x, y, z))
+    }
+
+    // The coordinates come in the order x, y and (in 3D) z, and `dims` are the sizes of the board
+    // along them.
+    fn resolve_coords<const N: usize>(
+        &self,
+        dims: [usize; N],
+        coords: [i64; N],
+    ) -> Option<[i64; N]> {
+        let inside = coords
+            .iter()
+            .zip(dims.iter())
+            .all(|(&c, &dim)| c >= 0 && c < dim as i64);
+        if inside {
+            return Some(coords);
+        }
+
+        match self {
+            Boundary::Torus => Some(std::array::from_fn(|i| wrap(coords[i], dims[i]))),
+            Boundary::Wall(_) => None,
+            Boundary::Reflective => Some(std::array::from_fn(|i| reflect(coords[i], dims[i]))),
+            Boundary::KleinBottle => {
+                let mut coords = coords;
+                if coords[1].div_euclid(dims[1] as i64) % 2 != 0 {
+                    coords[0] = dims[0] as i64 - 1 - coords[0];
+                }
+                Some(std::array::from_fn(|i| wrap(coords[i], dims[i])))
+            }
+        }
+    }
+
+    /// The cell read from beyond the edges of a walled board.
+    #[inline]
+    pub fn wall(&self) -> Option<&C> {
+        match self {
+            Boundary::Wall(cell) => Some(cell),
+            _ => None,
+        }
+    }
+}
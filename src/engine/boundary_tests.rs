@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod boundary_tests {
+    use crate::engine::boundary::*;
+    use crate::fields::{Pos2D, Pos3D};
+
+    use spectral::prelude::*;
+
+    fn resolve(boundary: &Boundary<bool>, x: i64, y: i64) -> Option<Pos2D> {
+        boundary.resolve(5, 3, &Pos2D::new(x, y))
+    }
+
+    #[test]
+    fn should_leave_positions_on_the_board() {
+        for boundary in &[
+            Boundary::Torus,
+            Boundary::Wall(false),
+            Boundary::Reflective,
+            Boundary::KleinBottle,
+        ] {
+            assert_that!(resolve(boundary, 4, 2)).is_equal_to(Some(Pos2D::new(4, 2)));
+            assert_that!(resolve(boundary, 0, 0)).is_equal_to(Some(Pos2D::new(0, 0)));
+        }
+    }
+
+    #[test]
+    fn should_wrap_torus() {
+        let torus = Boundary::Torus;
+        assert_that!(resolve(&torus, 5, 1)).is_equal_to(Some(Pos2D::new(0, 1)));
+        assert_that!(resolve(&torus, -1, -1)).is_equal_to(Some(Pos2D::new(4, 2)));
+        assert_that!(resolve(&torus, 12, 7)).is_equal_to(Some(Pos2D::new(2, 1)));
+    }
+
+    #[test]
+    fn should_hit_the_wall() {
+        let wall = Boundary::Wall(true);
+        assert_that!(resolve(&wall, 5, 1)).is_equal_to(None);
+        assert_that!(resolve(&wall, 2, -1)).is_equal_to(None);
+        assert_that!(wall.wall()).is_equal_to(Some(&true));
+        assert_that!(Boundary::<bool>::Torus.wall()).is_equal_to(None);
+    }
+
+    #[test]
+    fn should_reflect() {
+        let mirror = Boundary::Reflective;
+        assert_that!(resolve(&mirror, -1, 1)).is_equal_to(Some(Pos2D::new(0, 1)));
+        assert_that!(resolve(&mirror, -2, 1)).is_equal_to(Some(Pos2D::new(1, 1)));
+        assert_that!(resolve(&mirror, 5, 3)).is_equal_to(Some(Pos2D::new(4, 2)));
+        assert_that!(resolve(&mirror, 6, -1)).is_equal_to(Some(Pos2D::new(3, 0)));
+        assert_that!(resolve(&mirror, 10, 6)).is_equal_to(Some(Pos2D::new(0, 0)));
+    }
+
+    #[test]
+    fn should_twist_klein_bottle() {
+        let klein = Boundary::KleinBottle;
+        assert_that!(resolve(&klein, 5, 1)).is_equal_to(Some(Pos2D::new(0, 1)));
+        assert_that!(resolve(&klein, -1, 1)).is_equal_to(Some(Pos2D::new(4, 1)));
+        assert_that!(resolve(&klein, 0, 3)).is_equal_to(Some(Pos2D::new(4, 0)));
+        assert_that!(resolve(&klein, 1, -1)).is_equal_to(Some(Pos2D::new(3, 2)));
+        assert_that!(resolve(&klein, 1, 6)).is_equal_to(Some(Pos2D::new(1, 0)));
+    }
+
+    #[test]
+    fn should_resolve_in_3d() {
+        let resolve3d = |boundary: &Boundary<bool>, pos: Pos3D| boundary.resolve3d(5, 3, 2, &pos);
+        let pos = Pos3D::new(1, -1, 2);
+        assert_that!(resolve3d(&Boundary::Torus, pos)).is_equal_to(Some(Pos3D::new(1, 2, 0)));
+        assert_that!(resolve3d(&Boundary::Wall(false), pos)).is_equal_to(None);
+        assert_that!(resolve3d(&Boundary::Reflective, pos)).is_equal_to(Some(Pos3D::new(1, 0, 1)));
+        assert_that!(resolve3d(&Boundary::KleinBottle, pos)).is_equal_to(Some(Pos3D::new(3, 2, 0)));
+        assert_that!(resolve3d(&Boundary::KleinBottle, Pos3D::new(4, 1, 1)))
+            .is_equal_to(Some(Pos3D::new(4, 1, 1)));
+    }
+}
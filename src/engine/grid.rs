@@ -0,0 +1,66 @@
+use crate::utils::umap::UMap;
+
+/// What `Board` and `Board3D` have in common: the cells are kept in a `UMap`, under ids computed
+/// from their positions after applying the boundary. It lets the automatons share the code which
+/// changes and updates the cells.
+pub(crate) trait Grid: Clone {
+    type Cell: Copy + PartialEq;
+    type Pos;
+
+    /// The id of the cell at `pos`, or `None` if `pos` is behind a wall.
+    fn id(&self, pos: &Self::Pos) -> Option<usize>;
+    fn position(cell: &Self::Cell) -> Self::Pos;
+    fn cells(&self) -> &UMap<Self::Cell>;
+    fn cells_mut(&mut self) -> &mut UMap<Self::Cell>;
+    /// The next generation of `cell`, which sees the board as its neighbourhood.
+    fn update_cell(&self, cell: &Self::Cell) -> Self::Cell;
+}
+
+/// Puts the next generation of every cell of `board` into `target`, reusing its storage.
+pub(crate) fn update_into<G: Grid>(board: &G, target: &mut G) {
+    let map = target.cells_mut();
+    board
+        .cells()
+        .iter()
+        .for_each(|(id, cell)| map.put(id, board.update_cell(cell)));
+}
+
+/// A copy of `board` with `cells` in their places. The cells behind a wall are skipped.
+pub(crate) fn copy_and_update<G: Grid>(board: &G, cells: &[G::Cell]) -> G {
+    let mut next = board.clone();
+    for cell in cells {
+        if let Some(id) = board.id(&G::position(cell)) {
+            next.cells_mut().put(id, *cell);
+        }
+    }
+    next
+}
+
+/// A copy of `board` with the cells from `cells` under their ids.
+pub(crate) fn copy_and_put<G: Grid>(board: &G, cells: &UMap<G::Cell>) -> G {
+    let mut next = board.clone();
+    let map = next.cells_mut();
+    cells.iter().for_each(|(id, cell)| map.put(id, *cell));
+    next
+}
+
+/// Keeps `cell` in `changes` until `apply_changes`, unless it's behind a wall.
+pub(crate) fn add_change<G: Grid>(board: &G, changes: &mut UMap<G::Cell>, cell: &G::Cell) {
+    if let Some(id) = board.id(&G::position(cell)) {
+        changes.put(id, *cell);
+    }
+}
+
+/// Puts the `changes` on `board`, calling `on_change` with the id of every cell right after it's
+/// put. Empties `changes`, but keeps its capacity for the next ones.
+pub(crate) fn apply_changes<G: Grid>(
+    board: &mut G,
+    changes: &mut UMap<G::Cell>,
+    mut on_change: impl FnMut(&G, usize),
+) {
+    for (id, cell) in changes.iter() {
+        board.cells_mut().put(id, *cell);
+        on_change(board, id);
+    }
+    changes.clear();
+}
@@ -0,0 +1,408 @@
+use crate::engine::automaton::{AutomatonCell, Board};
+use crate::engine::life_rule::{Counting, LifeRule, RuleError};
+use crate::fields::Pos2D;
+
+use std::collections::HashMap;
+
+type NodeId = usize;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+// A square of 2^level x 2^level cells. Leaves (level 0) are single cells.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    population: u64,
+}
+
+/// Gosper's HashLife: an unbounded Life-like universe stored as a quadtree of hash-consed nodes,
+/// with the future of every node memoized. That lets it jump 2^k generations at once, which for
+/// regular patterns (guns, breeders, spaceships) is many orders of magnitude faster than `Board::update`.
+///
+/// Rules with B0 aren't supported: they would fill the whole infinite universe at once.
+///
+/// Memoized results are never forgotten, so memory grows with the number of distinct nodes seen;
+/// call `clear_cache` from time to time when running chaotic patterns for long.
+pub struct HashLife {
+    rule: LifeRule,
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    empty: Vec<NodeId>,
+    root: NodeId,
+    // the position of the root's top-left cell
+    origin: Pos2D,
+    generation: u64,
+}
+
+impl Default for HashLife {
+    fn default() -> Self {
+        HashLife::new(LifeRule::CONWAY).unwrap()
+    }
+}
+
+impl HashLife {
+    /// Fails with `RuleError::BirthOnZero` if the rule has B0.
+    pub fn new(rule: LifeRule) -> Result<Self, RuleError> {
+        if rule.born(0) {
+            return Err(RuleError::BirthOnZero);
+        }
+        let leaf = |population| Node {
+            level: 0,
+            nw: DEAD,
+            ne: DEAD,
+            sw: DEAD,
+            se: DEAD,
+            population,
+        };
+        let mut life = HashLife {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: Pos2D::new(0, 0),
+            generation: 0,
+        };
+        life.root = life.empty(3);
+        Ok(life)
+    }
+
+    pub fn from_cells(rule: LifeRule, cells: &[Pos2D]) -> Result<Self, RuleError> {
+        let mut life = HashLife::new(rule)?;
+        cells.iter().for_each(|pos| life.set_cell(pos, true));
+        Ok(life)
+    }
+
+    /// Copies the living cells of `board` into an empty universe, at the same positions. Note that
+    /// the universe has no edges: patterns which wrapped around the board won't do so anymore.
+    pub fn from_board<C: AutomatonCell>(
+        rule: LifeRule,
+        board: &Board<C>,
+        is_alive: impl Fn(&C) -> bool,
+    ) -> Result<Self, RuleError> {
+        let cells: Vec<Pos2D> = board
+            .iter()
+            .filter(|(_, c)| is_alive(c))
+            .map(|(_, c)| c.position())
+            .collect();
+        HashLife::from_cells(rule, &cells)
+    }
+
+    /// Creates a `width` x `height` board with the living cells from the area between `(0, 0)` and
+    /// `(width, height)`, made by `new_life`. The rest of the board is filled with `C::new`.
+    pub fn to_board<C: AutomatonCell>(
+        &self,
+        width: usize,
+        height: usize,
+        new_life: impl Fn(&Pos2D) -> C,
+    ) -> Board<C> {
+        let cells: Vec<C> = self
+            .live_cells()
+            .iter()
+            .filter(|p| p.x >= 0 && p.y >= 0 && p.x < width as i64 && p.y < height as i64)
+            .map(new_life)
+            .collect();
+        Board::new(width, height).copy_and_update(&cells)
+    }
+
+    #[inline]
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    #[inline]
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    /// Forgets all the memoized results and the nodes not used by the current pattern.
+    pub fn clear_cache(&mut self) {
+        let cells = self.live_cells();
+        // the rule was already checked
+        let mut life = HashLife::from_cells(self.rule, &cells).unwrap();
+        life.generation = self.generation;
+        *self = life;
+    }
+
+    #[inline]
+    fn size(&self, id: NodeId) -> i64 {
+        1 << self.nodes[id].level
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let key = [nw, ne, sw, se];
+        if let Some(&id) = self.index.get(&key) {
+            return id;
+        }
+        let n = &self.nodes;
+        let node = Node {
+            level: n[nw].level + 1,
+            nw,
+            ne,
+            sw,
+            se,
+            population: n[nw].population + n[ne].population + n[sw].population + n[se].population,
+        };
+        let id = self.nodes.len();
+        self.nodes.push(node);
+        self.index.insert(key, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let next = self.join(e, e, e, e);
+            self.empty.push(next);
+        }
+        self.empty[level as usize]
+    }
+
+    // The same cells, in a node twice as big.
+    fn expand(&mut self) {
+        let root = self.nodes[self.root];
+        let e = self.empty(root.level - 1);
+        let nw = self.join(e, e, e, root.nw);
+        let ne = self.join(e, e, root.ne, e);
+        let sw = self.join(e, root.sw, e, e);
+        let se = self.join(root.se, e, e, e);
+        let half = self.size(self.root) / 2;
+        self.root = self.join(nw, ne, sw, se);
+        self.origin = Pos2D::new(self.origin.x - half, self.origin.y - half);
+    }
+
+    fn centre(&mut self, id: NodeId) -> NodeId {
+        let n = self.nodes[id];
+        let (nw, ne, sw, se) = (
+            self.nodes[n.nw],
+            self.nodes[n.ne],
+            self.nodes[n.sw],
+            self.nodes[n.se],
+        );
+        self.join(nw.se, ne.sw, sw.ne, se.nw)
+    }
+
+    fn contains(&self, pos: &Pos2D) -> bool {
+        let size = self.size(self.root);
+        pos.x >= self.origin.x
+            && pos.y >= self.origin.y
+            && pos.x < self.origin.x + size
+            && pos.y < self.origin.y + size
+    }
+
+    pub fn set_cell(&mut self, pos: &Pos2D, alive: bool) {
+        while !self.contains(pos) {
+            self.expand();
+        }
+        let (x, y) = (pos.x - self.origin.x, pos.y - self.origin.y);
+        self.root = self.set(self.root, x, y, alive);
+    }
+
+    fn set(&mut self, id: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let n = self.nodes[id];
+        if n.level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = self.size(id) / 2;
+        match (x < half, y < half) {
+            (true, true) => {
+                let nw = self.set(n.nw, x, y, alive);
+                self.join(nw, n.ne, n.sw, n.se)
+            }
+            (false, true) => {
+                let ne = self.set(n.ne, x - half, y, alive);
+                self.join(n.nw, ne, n.sw, n.se)
+            }
+            (true, false) => {
+                let sw = self.set(n.sw, x, y - half, alive);
+                self.join(n.nw, n.ne, sw, n.se)
+            }
+            (false, false) => {
+                let se = self.set(n.se, x - half, y - half, alive);
+                self.join(n.nw, n.ne, n.sw, se)
+            }
+        }
+    }
+
+    pub fn get_cell(&self, pos: &Pos2D) -> bool {
+        if !self.contains(pos) {
+            return false;
+        }
+        let (mut x, mut y) = (pos.x - self.origin.x, pos.y - self.origin.y);
+        let mut id = self.root;
+        while self.nodes[id].level > 0 {
+            let n = self.nodes[id];
+            let half = self.size(id) / 2;
+            id = match (x < half, y < half) {
+                (true, true) => n.nw,
+                (false, true) => n.ne,
+                (true, false) => n.sw,
+                (false, false) => n.se,
+            };
+            if x >= half {
+                x -= half;
+            }
+            if y >= half {
+                y -= half;
+            }
+        }
+        id == ALIVE
+    }
+
+    /// Positions of all the living cells, row by row.
+    pub fn live_cells(&self) -> Vec<Pos2D> {
+        let mut cells = Vec::with_capacity(self.population() as usize);
+        self.collect(self.root, self.origin, &mut cells);
+        cells.sort_by_key(|p| (p.y, p.x));
+        cells
+    }
+
+    fn collect(&self, id: NodeId, corner: Pos2D, cells: &mut Vec<Pos2D>) {
+        let n = self.nodes[id];
+        if n.population == 0 {
+            return;
+        }
+        if n.level == 0 {
+            cells.push(corner);
+            return;
+        }
+        let half = self.size(id) / 2;
+        self.collect(n.nw, corner, cells);
+        self.collect(n.ne, Pos2D::new(corner.x + half, corner.y), cells);
+        self.collect(n.sw, Pos2D::new(corner.x, corner.y + half), cells);
+        self.collect(n.se, Pos2D::new(corner.x + half, corner.y + half), cells);
+    }
+
+    /// Advances the universe by 2^`k` generations at once.
+    pub fn step(&mut self, k: u8) {
+        // The pattern has to stay far enough from the edges for the result to hold all of it.
+        loop {
+            let level = self.nodes[self.root].level;
+            let inner = self.centre(self.root);
+            let inner = self.centre(inner);
+            if level >= k + 2 && self.nodes[inner].population == self.population() {
+                break;
+            }
+            self.expand();
+        }
+        self.expand();
+
+        let quarter = self.size(self.root) / 4;
+        self.root = self.successor(self.root, k);
+        self.origin = Pos2D::new(self.origin.x + quarter, self.origin.y + quarter);
+        self.generation += 1 << k;
+    }
+
+    /// Runs `generations` generations, in as few jumps as possible.
+    pub fn run(&mut self, generations: u64) {
+        (0..64)
+            .filter(|k| generations & (1 << k) != 0)
+            .for_each(|k| self.step(k as u8));
+    }
+
+    // The centre of the node (half its size), 2^j generations later.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let level = self.nodes[id].level;
+        debug_assert!(level >= 2 && j <= level - 2);
+        if level == 2 {
+            return self.life_4x4(id);
+        }
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+
+        let n = self.nodes[id];
+        let (a, b, c, d) = (
+            self.nodes[n.nw],
+            self.nodes[n.ne],
+            self.nodes[n.sw],
+            self.nodes[n.se],
+        );
+        let subnodes = [
+            n.nw,
+            self.join(a.ne, b.nw, a.se, b.sw),
+            n.ne,
+            self.join(a.sw, a.se, c.nw, c.ne),
+            self.join(a.se, b.sw, c.ne, d.nw),
+            self.join(b.sw, b.se, d.nw, d.ne),
+            n.sw,
+            self.join(c.ne, d.nw, c.se, d.sw),
+            n.se,
+        ];
+
+        // At full speed both halves of the way are computed recursively; otherwise the first
+        // half is skipped by just taking the centres.
+        let full = j == level - 2;
+        let mut r = [DEAD; 9];
+        for (i, &sub) in subnodes.iter().enumerate() {
+            r[i] = if full {
+                self.successor(sub, j - 1)
+            } else {
+                self.centre(sub)
+            };
+        }
+        let next = if full { j - 1 } else { j };
+
+        let s00 = self.join(r[0], r[1], r[3], r[4]);
+        let s01 = self.join(r[1], r[2], r[4], r[5]);
+        let s10 = self.join(r[3], r[4], r[6], r[7]);
+        let s11 = self.join(r[4], r[5], r[7], r[8]);
+        let nw = self.successor(s00, next);
+        let ne = self.successor(s01, next);
+        let sw = self.successor(s10, next);
+        let se = self.successor(s11, next);
+        let result = self.join(nw, ne, sw, se);
+
+        self.results.insert((id, j), result);
+        result
+    }
+
+    // The centre 2x2 of a 4x4 node, one generation later.
+    fn life_4x4(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        let n = self.nodes[id];
+        for (quarter, (qx, qy)) in [
+            (n.nw, (0, 0)),
+            (n.ne, (2, 0)),
+            (n.sw, (0, 2)),
+            (n.se, (2, 2)),
+        ]
+        .iter()
+        {
+            let q = self.nodes[*quarter];
+            grid[*qy][*qx] = q.nw == ALIVE;
+            grid[*qy][qx + 1] = q.ne == ALIVE;
+            grid[qy + 1][*qx] = q.sw == ALIVE;
+            grid[qy + 1][qx + 1] = q.se == ALIVE;
+        }
+
+        let neumann = self.rule.counting() == Counting::Neumann;
+        let cell = |x: usize, y: usize| {
+            let neighbours = (y - 1..=y + 1)
+                .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && (!neumann || nx == x || ny == y))
+                .filter(|&(nx, ny)| grid[ny][nx])
+                .count();
+
+            if self.rule.next(grid[y][x], neighbours) {
+                ALIVE
+            } else {
+                DEAD
+            }
+        };
+        let (nw, ne, sw, se) = (cell(1, 1), cell(2, 1), cell(1, 2), cell(2, 2));
+        self.join(nw, ne, sw, se)
+    }
+}
@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod hashlife_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::boundary::Boundary;
+    use crate::engine::hashlife::*;
+    use crate::engine::life_rule::LifeRule;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::fields::Pos2D;
+
+    use rand::{Rng, SeedableRng, XorShiftRng};
+    use spectral::prelude::*;
+    use std::collections::HashSet;
+
+    fn positions(cells: &[(i64, i64)]) -> Vec<Pos2D> {
+        let mut vec: Vec<Pos2D> = cells.iter().map(|&(x, y)| Pos2D::new(x, y)).collect();
+        vec.sort_by_key(|p| (p.y, p.x));
+        vec
+    }
+
+    fn glider(dx: i64, dy: i64) -> Vec<Pos2D> {
+        positions(&[
+            (1 + dx, dy),
+            (2 + dx, 1 + dy),
+            (dx, 2 + dy),
+            (1 + dx, 2 + dy),
+            (2 + dx, 2 + dy),
+        ])
+    }
+
+    #[test]
+    fn should_set_and_get_cells() {
+        let mut life = HashLife::default();
+        life.set_cell(&Pos2D::new(3, 4), true);
+        life.set_cell(&Pos2D::new(-100, 250), true);
+
+        assert_that!(life.get_cell(&Pos2D::new(3, 4))).is_true();
+        assert_that!(life.get_cell(&Pos2D::new(-100, 250))).is_true();
+        assert_that!(life.get_cell(&Pos2D::new(4, 3))).is_false();
+        assert_that!(life.population()).is_equal_to(2);
+
+        life.set_cell(&Pos2D::new(3, 4), false);
+        assert_that!(life.live_cells()).is_equal_to(positions(&[(-100, 250)]));
+    }
+
+    #[test]
+    fn should_blink() {
+        let mut life =
+            HashLife::from_cells(LifeRule::CONWAY, &positions(&[(0, 1), (1, 1), (2, 1)])).unwrap();
+        life.step(0);
+        assert_that!(life.live_cells()).is_equal_to(positions(&[(1, 0), (1, 1), (1, 2)]));
+        assert_that!(life.generation()).is_equal_to(1);
+        life.step(0);
+        assert_that!(life.live_cells()).is_equal_to(positions(&[(0, 1), (1, 1), (2, 1)]));
+    }
+
+    #[test]
+    fn should_move_glider_far_away() {
+        let mut life = HashLife::from_cells(LifeRule::CONWAY, &glider(0, 0)).unwrap();
+        life.step(2);
+        assert_that!(life.live_cells()).is_equal_to(glider(1, 1));
+
+        life.step(10);
+        assert_that!(life.generation()).is_equal_to(1028);
+        assert_that!(life.live_cells()).is_equal_to(glider(257, 257));
+    }
+
+    #[test]
+    fn should_run_any_number_of_generations() {
+        let mut life = HashLife::from_cells(LifeRule::CONWAY, &glider(0, 0)).unwrap();
+        life.run(4 * 1000 + 3);
+        assert_that!(life.generation()).is_equal_to(4003);
+        life.run(1);
+        assert_that!(life.live_cells()).is_equal_to(glider(1001, 1001));
+    }
+
+    #[test]
+    fn should_follow_the_board() {
+        let mut rng = XorShiftRng::from_seed([5, 6, 7, 8]);
+        let soup: Vec<GameOfLife> = Pos2D::from_dims(16, 16)
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .map(|p| GameOfLife::new_life(&Pos2D::new(p.x + 24, p.y + 24)))
+            .collect();
+
+        // The soup grows by at most one cell per generation, so it won't reach the walls.
+        let mut board =
+            Board::with_boundary(64, 64, Boundary::Wall(GameOfLife::new(&Pos2D::new(0, 0))))
+                .copy_and_update(&soup);
+        let mut life = HashLife::from_board(LifeRule::CONWAY, &board, |c| c.life).unwrap();
+        life.step(4);
+        (0..16).for_each(|_| board = board.update());
+
+        let from_hashlife: Vec<Pos2D> = life
+            .to_board(64, 64, GameOfLife::new_life)
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect();
+        let from_board: Vec<Pos2D> = board
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect();
+        assert_that!(from_hashlife).is_equal_to(&from_board);
+        assert_that!(life.population()).is_equal_to(from_board.len() as u64);
+    }
+
+    // a straightforward implementation on an unbounded plane, for comparison
+    fn brute_force(rule: &LifeRule, cells: &[Pos2D]) -> Vec<Pos2D> {
+        let alive: HashSet<Pos2D> = cells.iter().cloned().collect();
+        let candidates: HashSet<Pos2D> = cells
+            .iter()
+            .flat_map(|p| {
+                (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| Pos2D::new(p.x + dx, p.y + dy)))
+            })
+            .collect();
+        let mut next: Vec<Pos2D> = candidates
+            .into_iter()
+            .filter(|p| {
+                let neighbours = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                    .filter(|&d| d != (0, 0))
+                    .filter(|&(dx, dy)| alive.contains(&Pos2D::new(p.x + dx, p.y + dy)))
+                    .count();
+                rule.next(alive.contains(p), neighbours)
+            })
+            .collect();
+        next.sort_by_key(|p| (p.y, p.x));
+        next
+    }
+
+    #[test]
+    fn should_use_other_rules() {
+        let highlife = LifeRule::new(&[3, 6], &[2, 3]).unwrap();
+        let seeds = LifeRule::new(&[2], &[]).unwrap();
+        let mut rng = XorShiftRng::from_seed([8, 7, 6, 5]);
+        let soup: Vec<Pos2D> = Pos2D::from_dims(12, 12)
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .cloned()
+            .collect();
+
+        for rule in &[highlife, seeds] {
+            let mut expected = soup.clone();
+            (0..8).for_each(|_| expected = brute_force(rule, &expected));
+            let mut life = HashLife::from_cells(*rule, &soup).unwrap();
+            life.step(3);
+            assert_that!(life.live_cells()).is_equal_to(expected);
+        }
+    }
+
+    #[test]
+    fn should_reject_rules_with_birth_on_zero() {
+        let rule = "B0/S8".parse::<LifeRule>().unwrap();
+        assert_that!(HashLife::new(rule).is_err()).is_true();
+        assert_that!(HashLife::from_cells(rule, &glider(0, 0)).is_err()).is_true();
+    }
+
+    #[test]
+    fn should_keep_the_pattern_after_clearing_cache() {
+        let mut life = HashLife::from_cells(LifeRule::CONWAY, &glider(0, 0)).unwrap();
+        life.step(3);
+        life.clear_cache();
+        life.step(3);
+        assert_that!(life.generation()).is_equal_to(16);
+        assert_that!(life.live_cells()).is_equal_to(glider(4, 4));
+    }
+}
@@ -0,0 +1,420 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "json")]
+use std::convert::TryFrom;
+
+/// Which neighbours are counted by a `LifeRule`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Counting {
+    /// All eight cells around.
+    #[default]
+    Moore,
+    /// Only the four orthogonal ones. Written as a `V` at the end of the rule string.
+    Neumann,
+}
+
+impl Counting {
+    #[inline]
+    pub fn max(self) -> usize {
+        match self {
+            Counting::Moore => 8,
+            Counting::Neumann => 4,
+        }
+    }
+}
+
+/// An outer-totalistic rule for two-state cells: whether a cell is alive in the next generation
+/// depends only on whether it's alive now and on how many of its neighbours are.
+///
+/// Rules are usually given in the B/S notation, e.g. `"B3/S23".parse::<LifeRule>()` for Conway's
+/// Game of Life: a dead cell is born with three living neighbours and a living one survives with
+/// two or three. The old S/B notation (`"23/3"`) works too, and a `V` at the end (`"B1/S1V"`)
+/// switches from the Moore neighbourhood to von Neumann's.
+/// With the `json` feature it's serialized as its string, e.g. `"B3/S23"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "json",
+    derive(Serialize, Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct LifeRule {
+    // bit n is set if n living neighbours are enough
+    birth: u16,
+    survival: u16,
+    counting: Counting,
+}
+
+impl Default for LifeRule {
+    fn default() -> Self {
+        LifeRule::CONWAY
+    }
+}
+
+impl LifeRule {
+    /// B3/S23
+    pub const CONWAY: LifeRule = LifeRule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+        counting: Counting::Moore,
+    };
+
+    /// A rule with the Moore neighbourhood. Fails if any of the counts is larger than 8.
+    pub fn new(birth: &[usize], survival: &[usize]) -> Result<LifeRule, RuleError> {
+        let max = Counting::Moore.max();
+        let mask = |counts: &[usize]| {
+            counts.iter().try_fold(0u16, |acc, &n| {
+                if n <= max {
+                    Ok(acc | 1 << n)
+                } else {
+                    Err(RuleError::CountTooHigh { count: n, max })
+                }
+            })
+        };
+        Ok(LifeRule {
+            birth: mask(birth)?,
+            survival: mask(survival)?,
+            counting: Counting::Moore,
+        })
+    }
+
+    pub fn with_counting(self, counting: Counting) -> LifeRule {
+        LifeRule { counting, ..self }
+    }
+
+    #[inline]
+    pub fn counting(&self) -> Counting {
+        self.counting
+    }
+
+    #[inline]
+    pub fn born(&self, neighbours: usize) -> bool {
+        self.birth & 1 << neighbours != 0
+    }
+
+    #[inline]
+    pub fn survives(&self, neighbours: usize) -> bool {
+        self.survival & 1 << neighbours != 0
+    }
+
+    #[inline]
+    pub fn next(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survives(neighbours)
+        } else {
+            self.born(neighbours)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    Empty,
+    /// The rule should consist of two parts (three for `GenerationsRule`) separated with slashes.
+    WrongNumberOfParts {
+        expected: usize,
+        found: usize,
+    },
+    /// Either both parts start with `B` or `S`, or none of them does.
+    MixedNotation,
+    /// In the B/S notation, both `B` and `S` should appear once.
+    DuplicatePart(char),
+    InvalidCharacter(char),
+    /// A count of neighbours larger than the number of neighbours.
+    CountTooHigh {
+        count: usize,
+        max: usize,
+    },
+    /// The number of states of a `GenerationsRule` should be a number between 2 and 255.
+    InvalidStates(String),
+    /// A rule with B0 where it's not supported, e.g. in `HashLife`.
+    BirthOnZero,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::Empty => write!(f, "the rule is empty"),
+            RuleError::WrongNumberOfParts { expected, found } => write!(
+                f,
+                "expected {} parts separated with '/' (like \"B3/S23\" or \"B2/S/C3\"), found {}",
+                expected, found
+            ),
+            RuleError::MixedNotation => write!(
+                f,
+                "either both parts should start with 'B' or 'S' (\"B3/S23\"), or none (\"23/3\")"
+            ),
+            RuleError::DuplicatePart(c) => write!(f, "'{}' appears more than once", c),
+            RuleError::InvalidCharacter(c) => write!(f, "unexpected character '{}'", c),
+            RuleError::CountTooHigh { count, max } => write!(
+                f,
+                "{} neighbours is more than the neighbourhood has ({})",
+                count, max
+            ),
+            RuleError::InvalidStates(s) => write!(
+                f,
+                "the number of states should be between 2 and 255, found \"{}\"",
+                s
+            ),
+            RuleError::BirthOnZero => write!(
+                f,
+                "dead cells with no living neighbours can't be born (B0) in an unbounded universe"
+            ),
+        }
+    }
+}
+
+impl Error for RuleError {}
+
+impl FromStr for LifeRule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleError::Empty);
+        }
+
+        let (s, counting) = match s.strip_suffix(|c| c == 'V' || c == 'v') {
+            Some(rest) => (rest, Counting::Neumann),
+            None => (s, Counting::Moore),
+        };
+        let parts: Vec<&str> = s.split('/').collect();
+        if parts.len() != 2 {
+            return Err(RuleError::WrongNumberOfParts {
+                expected: 2,
+                found: parts.len(),
+            });
+        }
+
+        let counts = |part: &str| -> Result<u16, RuleError> {
+            part.chars().try_fold(0u16, |acc, c| match c.to_digit(10) {
+                Some(n) if n as usize <= counting.max() => Ok(acc | 1 << n),
+                Some(n) => Err(RuleError::CountTooHigh {
+                    count: n as usize,
+                    max: counting.max(),
+                }),
+                None => Err(RuleError::InvalidCharacter(c)),
+            })
+        };
+        let prefix = |part: &str| {
+            part.chars()
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .filter(|&c| c == 'B' || c == 'S')
+        };
+
+        let (birth, survival) = match (prefix(parts[0]), prefix(parts[1])) {
+            (None, None) => (parts[1], parts[0]),
+            (Some('B'), Some('S')) => (&parts[0][1..], &parts[1][1..]),
+            (Some('S'), Some('B')) => (&parts[1][1..], &parts[0][1..]),
+            (Some(c), Some(_)) => return Err(RuleError::DuplicatePart(c)),
+            _ => return Err(RuleError::MixedNotation),
+        };
+
+        Ok(LifeRule {
+            birth: counts(birth)?,
+            survival: counts(survival)?,
+            counting,
+        })
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |mask: u16| -> String {
+            (0..=8)
+                .filter(|n| mask & 1 << n != 0)
+                .map(|n| std::char::from_digit(n, 10).unwrap())
+                .collect()
+        };
+        let suffix = match self.counting {
+            Counting::Moore => "",
+            Counting::Neumann => "V",
+        };
+        write!(
+            f,
+            "B{}/S{}{}",
+            digits(self.birth),
+            digits(self.survival),
+            suffix
+        )
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<LifeRule> for String {
+    fn from(rule: LifeRule) -> Self {
+        rule.to_string()
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<String> for LifeRule {
+    type Error = RuleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// A Life-like rule for cells which don't die at once, but go through a number of refractory
+/// states first, e.g. Brian's Brain (`/2/3`) or Star Wars (`345/2/4`). State 0 is dead, 1 is
+/// alive, and only living cells are counted as neighbours. A living cell which doesn't survive
+/// moves to state 2, then to 3, and so on, until it's dead again after `states - 1`.
+///
+/// Like `LifeRule`, it can be written in the S/B/C notation (`"/2/3"`) or the B/S/C one
+/// (`"B2/S/C3"`), with an optional `V` at the end for the von Neumann neighbourhood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "json",
+    derive(Serialize, Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct GenerationsRule {
+    rule: LifeRule,
+    states: u8,
+}
+
+impl Default for GenerationsRule {
+    fn default() -> Self {
+        GenerationsRule::BRIANS_BRAIN
+    }
+}
+
+impl GenerationsRule {
+    /// B2/S/C3
+    pub const BRIANS_BRAIN: GenerationsRule = GenerationsRule {
+        rule: LifeRule {
+            birth: 1 << 2,
+            survival: 0,
+            counting: Counting::Moore,
+        },
+        states: 3,
+    };
+
+    /// With two states it's just the Life-like `rule`.
+    pub fn new(rule: LifeRule, states: u8) -> GenerationsRule {
+        assert!(states >= 2, "a cell needs at least two states");
+        GenerationsRule { rule, states }
+    }
+
+    #[inline]
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    #[inline]
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// The next state of a cell in `state` with `neighbours` living neighbours.
+    pub fn next(&self, state: u8, neighbours: usize) -> u8 {
+        match state {
+            0 if self.rule.born(neighbours) => 1,
+            0 => 0,
+            1 if self.rule.survives(neighbours) => 1,
+            s => (s + 1) % self.states,
+        }
+    }
+}
+
+impl FromStr for GenerationsRule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleError::Empty);
+        }
+
+        let (s, suffix) = match s.strip_suffix(|c| c == 'V' || c == 'v') {
+            Some(rest) => (rest, "V"),
+            None => (s, ""),
+        };
+        let parts: Vec<&str> = s.split('/').collect();
+        if parts.len() != 3 {
+            return Err(RuleError::WrongNumberOfParts {
+                expected: 3,
+                found: parts.len(),
+            });
+        }
+
+        let rule = format!("{}/{}{}", parts[0], parts[1], suffix).parse::<LifeRule>()?;
+        let states = parts[2]
+            .strip_prefix(|c| c == 'C' || c == 'c' || c == 'G' || c == 'g')
+            .unwrap_or(parts[2]);
+        match states.parse::<u8>() {
+            Ok(n) if n >= 2 => Ok(GenerationsRule { rule, states: n }),
+            _ => Err(RuleError::InvalidStates(parts[2].to_string())),
+        }
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rule = self.rule.to_string();
+        match rule.strip_suffix('V') {
+            Some(rule) => write!(f, "{}/C{}V", rule, self.states),
+            None => write!(f, "{}/C{}", rule, self.states),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<GenerationsRule> for String {
+    fn from(rule: GenerationsRule) -> Self {
+        rule.to_string()
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<String> for GenerationsRule {
+    type Error = RuleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// A Larger-than-Life rule: like `LifeRule`, but the neighbours are counted within `range` (see
+/// `Neighborhood::moore_radius` and `neumann_radius`), and births and survivals happen for counts
+/// within intervals instead of single numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LtlRule {
+    pub range: usize,
+    pub counting: Counting,
+    /// Whether the cell itself counts as its own neighbour.
+    pub middle: bool,
+    /// The smallest and largest count of living neighbours which brings a dead cell to life.
+    pub birth: (usize, usize),
+    /// The smallest and largest count of living neighbours which keeps a living cell alive.
+    pub survival: (usize, usize),
+}
+
+impl LtlRule {
+    /// Bugs: R5,C0,M1,S34..58,B34..45,NM
+    pub const BUGS: LtlRule = LtlRule {
+        range: 5,
+        counting: Counting::Moore,
+        middle: true,
+        birth: (34, 45),
+        survival: (34, 58),
+    };
+
+    #[inline]
+    pub fn next(&self, alive: bool, neighbours: usize) -> bool {
+        let (min, max) = if alive { self.survival } else { self.birth };
+        neighbours >= min && neighbours <= max
+    }
+}
+
+impl Default for LtlRule {
+    fn default() -> Self {
+        LtlRule::BUGS
+    }
+}
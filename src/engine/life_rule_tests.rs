@@ -0,0 +1,252 @@
+#[cfg(test)]
+mod life_rule_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::boundary::Boundary;
+    use crate::engine::life_rule::*;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::examples::generations::Generations;
+    use crate::examples::larger_than_life::LargerThanLife;
+    use crate::examples::life_like::LifeLike;
+    use crate::fields::Pos2D;
+
+    use rand::{Rng, SeedableRng, XorShiftRng};
+    use spectral::prelude::*;
+
+    fn parse(s: &str) -> Result<LifeRule, RuleError> {
+        s.parse::<LifeRule>()
+    }
+
+    #[test]
+    fn should_parse_conway() {
+        assert_that!(parse("B3/S23")).is_equal_to(Ok(LifeRule::CONWAY));
+        assert_that!(parse("b3/s23")).is_equal_to(Ok(LifeRule::CONWAY));
+        assert_that!(parse("S23/B3")).is_equal_to(Ok(LifeRule::CONWAY));
+        assert_that!(parse("23/3")).is_equal_to(Ok(LifeRule::CONWAY));
+        assert_that!(parse(" B3/S32 ")).is_equal_to(Ok(LifeRule::CONWAY));
+    }
+
+    #[test]
+    fn should_parse_other_rules() {
+        assert_that!(parse("B36/S23")).is_equal_to(LifeRule::new(&[3, 6], &[2, 3]));
+        assert_that!(parse("B2/S")).is_equal_to(LifeRule::new(&[2], &[]));
+        assert_that!(parse("B3678/S34678"))
+            .is_equal_to(LifeRule::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]));
+        assert_that!(parse("B1/S1V")).is_equal_to(Ok(LifeRule::new(&[1], &[1])
+            .unwrap()
+            .with_counting(Counting::Neumann)));
+    }
+
+    #[test]
+    fn should_display_in_bs_notation() {
+        assert_that!(LifeRule::CONWAY.to_string()).is_equal_to("B3/S23".to_string());
+        assert_that!(parse("4/2V").unwrap().to_string()).is_equal_to("B2/S4V".to_string());
+    }
+
+    #[test]
+    fn should_reject_invalid_rules() {
+        assert_that!(parse("")).is_equal_to(Err(RuleError::Empty));
+        assert_that!(parse("B3S23")).is_equal_to(Err(RuleError::WrongNumberOfParts {
+            expected: 2,
+            found: 1,
+        }));
+        assert_that!(parse("B3/S2/3")).is_equal_to(Err(RuleError::WrongNumberOfParts {
+            expected: 2,
+            found: 3,
+        }));
+        assert_that!(parse("B3/23")).is_equal_to(Err(RuleError::MixedNotation));
+        assert_that!(parse("B3/B23")).is_equal_to(Err(RuleError::DuplicatePart('B')));
+        assert_that!(parse("B3/Sx")).is_equal_to(Err(RuleError::InvalidCharacter('x')));
+        assert_that!(parse("B9/S23"))
+            .is_equal_to(Err(RuleError::CountTooHigh { count: 9, max: 8 }));
+        assert_that!(parse("B5/S1V"))
+            .is_equal_to(Err(RuleError::CountTooHigh { count: 5, max: 4 }));
+        assert_that!(LifeRule::new(&[3], &[2, 9]))
+            .is_equal_to(Err(RuleError::CountTooHigh { count: 9, max: 8 }));
+        assert_that!(LifeRule::new(&[16], &[]))
+            .is_equal_to(Err(RuleError::CountTooHigh { count: 16, max: 8 }));
+    }
+
+    #[test]
+    fn should_describe_errors() {
+        let err = parse("B3/S2a").unwrap_err();
+        assert_that!(err.to_string()).is_equal_to("unexpected character 'a'".to_string());
+    }
+
+    #[test]
+    fn should_behave_like_game_of_life() {
+        let mut rng = XorShiftRng::from_seed([2, 4, 6, 8]);
+        let alive: Vec<Pos2D> = Pos2D::from_dims(20, 20)
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .cloned()
+            .collect();
+        let rule = parse("B3/S23").unwrap();
+
+        let mut life = Board::<GameOfLife>::new(20, 20)
+            .copy_and_update(&alive.iter().map(GameOfLife::new_life).collect::<Vec<_>>());
+        let mut like = Board::from_fn(20, 20, Boundary::Torus, |p| LifeLike::dead(p, rule))
+            .copy_and_update(
+                &alive
+                    .iter()
+                    .map(|p| LifeLike::new_life(p, rule))
+                    .collect::<Vec<_>>(),
+            );
+
+        for _ in 0..20 {
+            life = life.update();
+            like = like.update();
+            let a: Vec<bool> = life.iter().map(|(_, c)| c.life).collect();
+            let b: Vec<bool> = like.iter().map(|(_, c)| c.life).collect();
+            assert_that!(a).is_equal_to(b);
+        }
+    }
+
+    #[test]
+    fn should_count_von_neumann_neighbours() {
+        // B1/S1V: a single cell dies of loneliness, but gives birth to its four neighbours
+        let rule = parse("B1/S1V").unwrap();
+        let mut board = Board::from_fn(9, 9, Boundary::Torus, |p| LifeLike::dead(p, rule))
+            .copy_and_update_one(&LifeLike::new_life(&Pos2D::new(4, 4), rule));
+        board = board.update();
+
+        let alive: Vec<Pos2D> = board
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect();
+        assert_that!(alive).is_equal_to(vec![
+            Pos2D::new(4, 3),
+            Pos2D::new(3, 4),
+            Pos2D::new(5, 4),
+            Pos2D::new(4, 5),
+        ]);
+    }
+
+    #[test]
+    fn should_parse_generations_rules() {
+        let brain = GenerationsRule::BRIANS_BRAIN;
+        assert_that!("/2/3".parse::<GenerationsRule>()).is_equal_to(Ok(brain));
+        assert_that!("B2/S/C3".parse::<GenerationsRule>()).is_equal_to(Ok(brain));
+        assert_that!("345/2/4".parse::<GenerationsRule>()).is_equal_to(Ok(GenerationsRule::new(
+            LifeRule::new(&[2], &[3, 4, 5]).unwrap(),
+            4,
+        )));
+        assert_that!(brain.to_string()).is_equal_to("B2/S/C3".to_string());
+        assert_that!("/2/3v".parse::<GenerationsRule>().unwrap().to_string())
+            .is_equal_to("B2/S/C3V".to_string());
+    }
+
+    #[test]
+    fn should_reject_invalid_generations_rules() {
+        assert_that!("B2/S".parse::<GenerationsRule>()).is_equal_to(Err(
+            RuleError::WrongNumberOfParts {
+                expected: 3,
+                found: 2,
+            },
+        ));
+        assert_that!("/2/1".parse::<GenerationsRule>())
+            .is_equal_to(Err(RuleError::InvalidStates("1".to_string())));
+        assert_that!("B2/S/Cx".parse::<GenerationsRule>())
+            .is_equal_to(Err(RuleError::InvalidStates("Cx".to_string())));
+        assert_that!("/9/3".parse::<GenerationsRule>())
+            .is_equal_to(Err(RuleError::CountTooHigh { count: 9, max: 8 }));
+    }
+
+    #[test]
+    fn should_go_through_refractory_states() {
+        let star_wars: GenerationsRule = "345/2/4".parse().unwrap();
+        assert_that!(star_wars.next(0, 2)).is_equal_to(1);
+        assert_that!(star_wars.next(0, 3)).is_equal_to(0);
+        assert_that!(star_wars.next(1, 4)).is_equal_to(1);
+        assert_that!(star_wars.next(1, 2)).is_equal_to(2);
+        assert_that!(star_wars.next(2, 2)).is_equal_to(3);
+        assert_that!(star_wars.next(3, 2)).is_equal_to(0);
+    }
+
+    #[test]
+    fn should_run_brians_brain() {
+        let rule = GenerationsRule::BRIANS_BRAIN;
+        let mut board = Board::from_fn(6, 6, Boundary::Torus, |p| Generations::dead(p, rule))
+            .copy_and_update(&[
+                Generations::new_life(&Pos2D::new(2, 2), rule),
+                Generations::new_life(&Pos2D::new(3, 2), rule),
+            ]);
+        let states = |board: &Board<Generations>| -> Vec<(i64, i64, u8)> {
+            board
+                .iter()
+                .filter(|(_, c)| c.state != 0)
+                .map(|(_, c)| (c.pos.x, c.pos.y, c.state))
+                .collect()
+        };
+
+        board = board.update();
+        assert_that!(states(&board)).is_equal_to(vec![
+            (2, 1, 1),
+            (3, 1, 1),
+            (2, 2, 2),
+            (3, 2, 2),
+            (2, 3, 1),
+            (3, 3, 1),
+        ]);
+        board = board.update();
+        assert_that!(board.get(&Pos2D::new(2, 2)).unwrap().state).is_equal_to(0);
+        assert_that!(board.get(&Pos2D::new(2, 1)).unwrap().state).is_equal_to(2);
+    }
+
+    #[test]
+    fn should_behave_like_game_of_life_with_range_one() {
+        let rule = LtlRule {
+            range: 1,
+            counting: Counting::Moore,
+            middle: false,
+            birth: (3, 3),
+            survival: (2, 3),
+        };
+        let mut rng = XorShiftRng::from_seed([1, 3, 5, 7]);
+        let alive: Vec<Pos2D> = Pos2D::from_dims(20, 20)
+            .iter()
+            .filter(|_| rng.gen::<bool>())
+            .cloned()
+            .collect();
+
+        let mut life = Board::<GameOfLife>::new(20, 20)
+            .copy_and_update(&alive.iter().map(GameOfLife::new_life).collect::<Vec<_>>());
+        let mut ltl = Board::from_fn(20, 20, Boundary::Torus, |p| LargerThanLife::dead(p, rule))
+            .copy_and_update(
+                &alive
+                    .iter()
+                    .map(|p| LargerThanLife::new_life(p, rule))
+                    .collect::<Vec<_>>(),
+            );
+
+        for _ in 0..20 {
+            life = life.update();
+            ltl = ltl.update();
+            let a: Vec<bool> = life.iter().map(|(_, c)| c.life).collect();
+            let b: Vec<bool> = ltl.iter().map(|(_, c)| c.life).collect();
+            assert_that!(a).is_equal_to(b);
+        }
+    }
+
+    #[test]
+    fn should_count_within_range() {
+        // with M1, a full 11x11 square counts 121 cells, so Bugs kills its middle
+        let bugs = LtlRule::BUGS;
+        let mut board = Board::from_fn(20, 20, Boundary::Torus, |p| {
+            if p.x < 11 && p.y < 11 {
+                LargerThanLife::new_life(p, bugs)
+            } else {
+                LargerThanLife::dead(p, bugs)
+            }
+        });
+        board = board.update();
+        assert_that!(board.get(&Pos2D::new(5, 5)).unwrap().life).is_false();
+        // in the corner, 6x6 = 36 cells are alive
+        assert_that!(board.get(&Pos2D::new(0, 0)).unwrap().life).is_true();
+        // below the square: 3x11 = 33 living cells are too few to be born, 4x11 = 44 are enough
+        assert_that!(board.get(&Pos2D::new(5, 13)).unwrap().life).is_false();
+        assert_that!(board.get(&Pos2D::new(5, 12)).unwrap().life).is_true();
+
+        assert_that!(LargerThanLife::new(&Pos2D::new(0, 0)).radius()).is_equal_to(5);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::engine::automaton::{AutomatonCell, Board};
+use crate::fields::Pos2D;
+
+use std::marker::PhantomData;
+
+/// A cell which can be moved to another place on the board by a block rule.
+pub trait BlockCell: AutomatonCell {
+    fn with_position(&self, pos: &Pos2D) -> Self;
+}
+
+/// A block automaton on the Margolus neighbourhood: the board is split into 2x2 blocks and each
+/// block is replaced with the result of `rule`. In even generations the blocks start at `(0, 0)`,
+/// and in odd ones at `(1, 1)`, so that the cells can move between blocks.
+///
+/// The blocks are given to the rule as `[top left, top right, bottom left, bottom right]`, and
+/// the cells it returns are moved to the positions of their slots, so e.g. a rule swapping
+/// the top and bottom cells moves them up and down. A block which doesn't fit on the board (behind
+/// a wall, or over the edge of a torus with an odd width or height) is left as it is.
+///
+/// `update` takes the place of `Board::update`, e.g. in `Automaton::next_with`.
+/// The automaton is generic over the rule, so calling it isn't a dynamic dispatch.
+#[derive(Clone)]
+pub struct Margolus<C: BlockCell, F = fn(&[C; 4]) -> [C; 4]> {
+    rule: F,
+    cell: PhantomData<C>,
+}
+
+impl<C: BlockCell, F: Fn(&[C; 4]) -> [C; 4]> Margolus<C, F> {
+    /// The rule can be a function, like `Sand::fall`, or a closure, e.g. with parameters.
+    pub fn new(rule: F) -> Self {
+        Margolus {
+            rule,
+            cell: PhantomData,
+        }
+    }
+
+    /// The position of the top left corner of the first block in the given generation.
+    #[inline]
+    pub fn offset(generation: u64) -> i64 {
+        (generation % 2) as i64
+    }
+
+    pub fn update(&self, board: &Board<C>) -> Board<C> {
+        let (width, height) = (board.width() as i64, board.height() as i64);
+        let offset = Margolus::<C, F>::offset(board.generation());
+        let mut used = vec![false; board.width() * board.height()];
+        let mut cells = Vec::with_capacity(board.width() * board.height());
+
+        for y in (offset..offset + height).step_by(2) {
+            for x in (offset..offset + width).step_by(2) {
+                let block = [
+                    board.get(&Pos2D::new(x, y)),
+                    board.get(&Pos2D::new(x + 1, y)),
+                    board.get(&Pos2D::new(x, y + 1)),
+                    board.get(&Pos2D::new(x + 1, y + 1)),
+                ];
+                if block.iter().any(|c| c.is_none()) {
+                    continue;
+                }
+                let block = [
+                    *block[0].unwrap(),
+                    *block[1].unwrap(),
+                    *block[2].unwrap(),
+                    *block[3].unwrap(),
+                ];
+                let ids = block.map(|c| {
+                    let pos = c.position();
+                    pos.y as usize * board.width() + pos.x as usize
+                });
+                if !(0..4).all(|i| !used[ids[i]] && !ids[..i].contains(&ids[i])) {
+                    continue;
+                }
+                ids.iter().for_each(|&id| used[id] = true);
+
+                let result = (self.rule)(&block);
+                for (cell, old) in result.iter().zip(block.iter()) {
+                    cells.push(cell.with_position(&old.position()));
+                }
+            }
+        }
+
+        let mut next = board.copy_and_update(&cells);
+        next.set_generation(board.generation() + 1);
+        next
+    }
+}
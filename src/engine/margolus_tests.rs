@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod margolus_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::boundary::Boundary;
+    use crate::engine::margolus::*;
+    use crate::engine::statistics::Statistics;
+    use crate::examples::sand::Sand;
+    use crate::fields::Pos2D;
+
+    use spectral::prelude::*;
+
+    fn grains(board: &Board<Sand>) -> Vec<Pos2D> {
+        board
+            .iter()
+            .filter(|(_, c)| c.grain)
+            .map(|(_, c)| c.pos)
+            .collect()
+    }
+
+    fn sand_board(width: usize, height: usize, grains: &[(i64, i64)]) -> Board<Sand> {
+        let wall = Sand::new_grain(&Pos2D::new(-1, -1));
+        let cells: Vec<Sand> = grains
+            .iter()
+            .map(|&(x, y)| Sand::new_grain(&Pos2D::new(x, y)))
+            .collect();
+        Board::with_boundary(width, height, Boundary::Wall(wall)).copy_and_update(&cells)
+    }
+
+    #[test]
+    fn should_alternate_offset() {
+        assert_that!(Margolus::<Sand>::offset(0)).is_equal_to(0);
+        assert_that!(Margolus::<Sand>::offset(1)).is_equal_to(1);
+        assert_that!(Margolus::<Sand>::offset(6)).is_equal_to(0);
+    }
+
+    #[test]
+    fn should_drop_sand_to_the_floor() {
+        let margolus = Margolus::new(Sand::fall);
+        let mut board = sand_board(4, 6, &[(1, 0)]);
+        for _ in 0..10 {
+            board = margolus.update(&board);
+        }
+        assert_that!(grains(&board)).is_equal_to(vec![Pos2D::new(1, 5)]);
+        assert_that!(board.generation()).is_equal_to(10);
+        // the cells know where they are
+        assert_that!(board
+            .iter()
+            .all(|(id, c)| c.pos == Pos2D::new(id as i64 % 4, id as i64 / 4)))
+        .is_true();
+    }
+
+    #[test]
+    fn should_pile_sand_up() {
+        let margolus = Margolus::new(Sand::fall);
+        let mut board = sand_board(6, 6, &[(2, 0), (2, 1), (2, 2), (3, 3)]);
+        for _ in 0..20 {
+            board = margolus.update(&board);
+        }
+        let pile = grains(&board);
+        assert_that!(pile.len()).is_equal_to(4);
+        assert_that!(pile.iter().all(|p| p.y >= 4)).is_true();
+    }
+
+    // Moves a cell to the opposite corner of its block.
+    fn rotate(block: &[Sand; 4]) -> [Sand; 4] {
+        [block[3], block[2], block[1], block[0]]
+    }
+
+    #[test]
+    fn should_move_particle_across_blocks() {
+        let margolus = Margolus::new(rotate);
+        let mut board =
+            Board::<Sand>::new(6, 6).copy_and_update_one(&Sand::new_grain(&Pos2D::new(0, 0)));
+        for i in 1..=8 {
+            board = margolus.update(&board);
+            assert_that!(grains(&board)).is_equal_to(vec![Pos2D::new(i % 6, i % 6)]);
+        }
+    }
+
+    #[test]
+    fn should_leave_blocks_over_odd_edges() {
+        let margolus = Margolus::new(rotate);
+        let mut board = Board::<Sand>::new(5, 5).copy_and_update(&[
+            Sand::new_grain(&Pos2D::new(4, 4)),
+            Sand::new_grain(&Pos2D::new(1, 2)),
+        ]);
+        board = margolus.update(&board);
+        assert_that!(grains(&board)).is_equal_to(vec![Pos2D::new(0, 3), Pos2D::new(4, 4)]);
+    }
+
+    #[test]
+    fn should_take_closures_as_rules() {
+        let steps = 3;
+        let margolus = Margolus::new(move |block: &[Sand; 4]| {
+            (0..steps).fold(*block, |block, _| rotate(&block))
+        });
+        let board =
+            Board::<Sand>::new(6, 6).copy_and_update_one(&Sand::new_grain(&Pos2D::new(0, 0)));
+        assert_that!(grains(&margolus.update(&board))).is_equal_to(vec![Pos2D::new(1, 1)]);
+    }
+
+    #[test]
+    fn should_step_automaton_with_margolus() {
+        let margolus = Margolus::new(Sand::fall);
+        let mut auto = Automaton::from_board(sand_board(6, 6, &[(2, 0), (2, 1), (2, 2)]));
+        auto.set_history_length(10);
+        auto.set_cycle_detection(true);
+        auto.set_statistics(Statistics::new(|c: &Sand| c.grain));
+        auto.add_change(&Sand::new_grain(&Pos2D::new(3, 3)));
+
+        for _ in 0..20 {
+            auto.next_with(|board| margolus.update(board));
+        }
+        assert_that!(auto.generation()).is_equal_to(20);
+        assert_that!(grains(auto.board()).iter().all(|p| p.y >= 4)).is_true();
+        assert_that!(auto.stability().map(|s| s.period)).is_equal_to(Some(1));
+        let stats = auto.statistics::<bool>().unwrap();
+        assert_that!(stats.samples().len()).is_equal_to(21);
+        assert_that!(stats.count(0, &true)).is_equal_to(Some(3));
+        assert_that!((1..=20).all(|g| stats.count(g, &true) == Some(4))).is_true();
+
+        // the offset of the blocks follows the generation, also after going back
+        let settled = auto.board().clone();
+        auto.step_back(10);
+        assert_that!(auto.generation()).is_equal_to(10);
+        for _ in 0..10 {
+            auto.next_with(|board| margolus.update(board));
+        }
+        assert_that!(auto.board()).is_equal_to(&settled);
+    }
+}
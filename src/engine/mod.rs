@@ -1 +1,37 @@
 pub mod automaton;
+pub mod automaton3d;
+pub mod boundary;
+mod grid;
+pub mod hashlife;
+pub mod life_rule;
+pub mod margolus;
+pub mod observer;
+pub mod snapshot;
+pub mod stability;
+pub mod statistics;
+pub mod template;
+
+#[cfg(test)]
+mod automaton3d_tests;
+#[cfg(test)]
+mod automaton_tests;
+#[cfg(test)]
+mod boundary_tests;
+#[cfg(test)]
+mod hashlife_tests;
+#[cfg(test)]
+mod life_rule_tests;
+#[cfg(test)]
+mod margolus_tests;
+#[cfg(test)]
+mod observer_tests;
+#[cfg(test)]
+mod snapshot_tests;
+#[cfg(test)]
+mod stability_tests;
+#[cfg(test)]
+mod statistics_tests;
+#[cfg(test)]
+mod template_tests;
+#[cfg(test)]
+mod test_helpers;
@@ -0,0 +1,83 @@
+use crate::engine::automaton::AutomatonCell;
+use crate::fields::Pos2D;
+
+/// Identifies an observer registered with `Automaton::observe`, e.g. to remove it later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(usize);
+
+type Filter<C> = Box<Fn(&C, &C) -> bool + Send + Sync>;
+type Callback<C> = Box<FnMut(&Pos2D, &C, &C) + Send + Sync>;
+
+struct Observer<C: AutomatonCell> {
+    id: ObserverId,
+    filter: Filter<C>,
+    callback: Callback<C>,
+}
+
+/// The callbacks to call when cells change, each with a filter on the old and the new cell.
+/// Closures can't be copied, so a copy of `Observers` is empty: a cloned automaton starts
+/// without observers.
+pub struct Observers<C: AutomatonCell> {
+    list: Vec<Observer<C>>,
+    next_id: usize,
+}
+
+impl<C: AutomatonCell> Observers<C> {
+    pub fn add(
+        &mut self,
+        filter: impl Fn(&C, &C) -> bool + Send + Sync + 'static,
+        callback: impl FnMut(&Pos2D, &C, &C) + Send + Sync + 'static,
+    ) -> ObserverId {
+        let id = ObserverId(self.next_id);
+        self.next_id += 1;
+        self.list.push(Observer {
+            id,
+            filter: Box::new(filter),
+            callback: Box::new(callback),
+        });
+        id
+    }
+
+    /// Returns false if there's no observer with this id.
+    pub fn remove(&mut self, id: ObserverId) -> bool {
+        let len = self.list.len();
+        self.list.retain(|observer| observer.id != id);
+        self.list.len() < len
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Calls every observer which accepts the change of the cell at `pos` from `old` to `new`.
+    pub fn notify(&mut self, pos: &Pos2D, old: &C, new: &C) {
+        self.list
+            .iter_mut()
+            .filter(|observer| (observer.filter)(old, new))
+            .for_each(|observer| (observer.callback)(pos, old, new));
+    }
+}
+
+impl<C: AutomatonCell> Default for Observers<C> {
+    fn default() -> Self {
+        Observers {
+            list: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<C: AutomatonCell> Clone for Observers<C> {
+    fn clone(&self) -> Self {
+        Observers {
+            list: Vec::new(),
+            next_id: self.next_id,
+        }
+    }
+}
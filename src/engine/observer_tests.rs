@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod observer_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::observer::Observers;
+    use crate::engine::test_helpers::{assert_send_sync, blinker};
+    use crate::examples::forest_fire::{ForestFire, Tree};
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::fields::Pos2D;
+
+    use spectral::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn should_report_changed_positions() {
+        let mut auto = blinker(8);
+        assert_that!(auto.changed_positions().is_empty()).is_true();
+
+        auto.apply_changes();
+        assert_that!(auto.changed_positions()).is_equal_to(vec![
+            Pos2D::new(1, 2),
+            Pos2D::new(2, 2),
+            Pos2D::new(3, 2),
+        ]);
+
+        auto.next();
+        let changes: Vec<(Pos2D, bool, bool)> = auto
+            .last_changes()
+            .map(|(pos, old, new)| (pos, old.life, new.life))
+            .collect();
+        assert_that!(changes).is_equal_to(vec![
+            (Pos2D::new(2, 1), false, true),
+            (Pos2D::new(1, 2), true, false),
+            (Pos2D::new(3, 2), true, false),
+            (Pos2D::new(2, 3), false, true),
+        ]);
+    }
+
+    #[test]
+    fn should_merge_applied_changes_with_the_generation() {
+        let mut auto = blinker(8);
+        // the centre of the blinker is added and stays alive, the ends are added and die
+        auto.next();
+        assert_that!(auto.changed_positions()).is_equal_to(vec![
+            Pos2D::new(2, 1),
+            Pos2D::new(2, 2),
+            Pos2D::new(2, 3),
+        ]);
+
+        // a cell added and killed in the same step doesn't count
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(6, 6)));
+        auto.next();
+        assert_that!(auto.changed_positions()).is_equal_to(vec![
+            Pos2D::new(2, 1),
+            Pos2D::new(1, 2),
+            Pos2D::new(3, 2),
+            Pos2D::new(2, 3),
+        ]);
+    }
+
+    #[test]
+    fn should_notify_filtered_observers() {
+        let mut auto = blinker(8);
+        let born = Arc::new(Mutex::new(Vec::new()));
+        let died = Arc::new(Mutex::new(0));
+
+        let b = born.clone();
+        auto.observe(
+            |old: &GameOfLife, new: &GameOfLife| !old.life && new.life,
+            move |pos, _, _| b.lock().unwrap().push(*pos),
+        );
+        let d = died.clone();
+        let id = auto.observe(
+            |old: &GameOfLife, new: &GameOfLife| old.life && !new.life,
+            move |_, _, _| *d.lock().unwrap() += 1,
+        );
+
+        // the blinker is added in the same step
+        auto.next();
+        assert_that!(*born.lock().unwrap()).is_equal_to(vec![
+            Pos2D::new(2, 1),
+            Pos2D::new(2, 2),
+            Pos2D::new(2, 3),
+        ]);
+        assert_that!(*died.lock().unwrap()).is_equal_to(0);
+
+        auto.next();
+        assert_that!(*died.lock().unwrap()).is_equal_to(2);
+
+        assert_that!(auto.remove_observer(id)).is_true();
+        assert_that!(auto.remove_observer(id)).is_false();
+        auto.next();
+        assert_that!(born.lock().unwrap().len()).is_equal_to(7);
+        assert_that!(*died.lock().unwrap()).is_equal_to(2);
+    }
+
+    #[test]
+    fn should_notify_about_fire() {
+        let mut auto = Automaton::<ForestFire>::new(10, 10);
+        for pos in Pos2D::from_dims(10, 10) {
+            auto.add_change(&ForestFire::new_tree(&pos));
+        }
+        auto.add_change(&ForestFire {
+            tree: Tree::Burning,
+            pos: Pos2D::new(5, 5),
+        });
+        let fires = Arc::new(Mutex::new(Vec::new()));
+        let f = fires.clone();
+        auto.observe(
+            |old: &ForestFire, new: &ForestFire| {
+                old.tree != Tree::Burning && new.tree == Tree::Burning
+            },
+            move |pos, _, _| f.lock().unwrap().push(*pos),
+        );
+
+        auto.apply_changes();
+        assert_that!(*fires.lock().unwrap()).is_equal_to(vec![Pos2D::new(5, 5)]);
+        auto.next();
+        assert_that!(fires.lock().unwrap().len()).is_equal_to(9);
+    }
+
+    #[test]
+    fn should_notify_about_undone_changes() {
+        let mut auto = blinker(8);
+        auto.set_history_length(3);
+        auto.next();
+        let count = Arc::new(Mutex::new(0));
+        let c = count.clone();
+        auto.observe(|_, _| true, move |_, _, _| *c.lock().unwrap() += 1);
+
+        auto.rewind_to(0);
+        assert_that!(*count.lock().unwrap()).is_equal_to(3);
+        assert_that!(auto.last_changes().all(|(_, old, _)| old.life)).is_true();
+    }
+
+    #[test]
+    fn should_not_clone_observers() {
+        let mut auto = blinker(8);
+        let count = Arc::new(Mutex::new(0));
+        let c = count.clone();
+        auto.observe(|_, _| true, move |_, _, _| *c.lock().unwrap() += 1);
+
+        let mut copy = auto.clone();
+        copy.next();
+        assert_that!(*count.lock().unwrap()).is_equal_to(0);
+        auto.next();
+        assert_that!(*count.lock().unwrap()).is_equal_to(3);
+    }
+
+    #[test]
+    fn should_be_send_and_sync() {
+        assert_send_sync::<Observers<GameOfLife>>();
+    }
+}
@@ -0,0 +1,268 @@
+//! Saving and restoring the state of an `Automaton`, e.g. for save-games and bug reports.
+//!
+//! A snapshot holds the board with its boundary, the pending changes (see `Automaton::add_change`),
+//! the generation and the seed. It doesn't hold the settings of the automaton, like the schedule,
+//! the update mode, or the history.
+//!
+//! The binary format works for cells implementing `SnapshotCell`. With the `json` feature,
+//! snapshots of cells implementing serde's `Serialize` and `Deserialize` can be saved as JSON too.
+use crate::engine::automaton::AutomatonCell;
+use crate::engine::boundary::Boundary;
+use crate::fields::Pos2D;
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+/// The version of the snapshot formats. Snapshots of other versions can't be loaded.
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 4] = b"GAIL";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The data doesn't start like a snapshot.
+    NotASnapshot,
+    IncompatibleVersion {
+        found: u16,
+        supported: u16,
+    },
+    /// The data ends in the middle of the snapshot.
+    UnexpectedEnd,
+    /// The data can't be read, or doesn't make a valid automaton.
+    Invalid(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::NotASnapshot => write!(f, "the data is not a snapshot"),
+            SnapshotError::IncompatibleVersion { found, supported } => write!(
+                f,
+                "the snapshot has version {}, but only version {} is supported",
+                found, supported
+            ),
+            SnapshotError::UnexpectedEnd => write!(f, "the snapshot ends unexpectedly"),
+            SnapshotError::Invalid(reason) => write!(f, "invalid snapshot: {}", reason),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+/// A cell which can be saved in a binary snapshot. Only its state has to be saved: the snapshot
+/// takes care of the position. The `read_*` and `write_*` functions of this module may help.
+pub trait SnapshotCell: AutomatonCell {
+    /// Writes at least one byte, so that the size of the board can be checked against the data.
+    fn save(&self, out: &mut Vec<u8>);
+
+    /// Reads the state written by `save` from the beginning of `input` and moves `input` past it.
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError>;
+}
+
+/// The state of an automaton, as returned by `Automaton::snapshot`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Snapshot<C> {
+    pub width: usize,
+    pub height: usize,
+    pub boundary: Boundary<C>,
+    pub seed: u64,
+    pub generation: u64,
+    /// All the cells of the board, row by row.
+    pub cells: Vec<C>,
+    /// The changes which weren't applied yet.
+    pub changes: Vec<C>,
+}
+
+pub fn read_bytes<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8], SnapshotError> {
+    if input.len() < n {
+        return Err(SnapshotError::UnexpectedEnd);
+    }
+    let (bytes, rest) = input.split_at(n);
+    *input = rest;
+    Ok(bytes)
+}
+
+pub fn read_u8(input: &mut &[u8]) -> Result<u8, SnapshotError> {
+    read_bytes(input, 1).map(|bytes| bytes[0])
+}
+
+pub fn read_bool(input: &mut &[u8]) -> Result<bool, SnapshotError> {
+    match read_u8(input)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        b => Err(SnapshotError::Invalid(format!("{} is not a boolean", b))),
+    }
+}
+
+pub fn read_u64(input: &mut &[u8]) -> Result<u64, SnapshotError> {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(read_bytes(input, 8)?);
+    Ok(u64::from_le_bytes(bytes))
+}
+
+pub fn read_i64(input: &mut &[u8]) -> Result<i64, SnapshotError> {
+    read_u64(input).map(|n| n as i64)
+}
+
+pub fn read_string(input: &mut &[u8]) -> Result<String, SnapshotError> {
+    let len = read_u64(input)? as usize;
+    let bytes = read_bytes(input, len)?;
+    String::from_utf8(bytes.to_vec())
+        .map_err(|_| SnapshotError::Invalid("a string is not valid UTF-8".to_string()))
+}
+
+#[inline]
+pub fn write_u64(out: &mut Vec<u8>, n: u64) {
+    out.extend_from_slice(&n.to_le_bytes());
+}
+
+pub fn write_string(out: &mut Vec<u8>, s: &str) {
+    write_u64(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_pos(out: &mut Vec<u8>, pos: &Pos2D) {
+    write_u64(out, pos.x as u64);
+    write_u64(out, pos.y as u64);
+}
+
+fn read_pos(input: &mut &[u8]) -> Result<Pos2D, SnapshotError> {
+    Ok(Pos2D::new(read_i64(input)?, read_i64(input)?))
+}
+
+fn read_version(found: u16) -> Result<(), SnapshotError> {
+    if found == SNAPSHOT_VERSION {
+        Ok(())
+    } else {
+        Err(SnapshotError::IncompatibleVersion {
+            found,
+            supported: SNAPSHOT_VERSION,
+        })
+    }
+}
+
+impl<C: SnapshotCell> Snapshot<C> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        write_u64(&mut out, self.width as u64);
+        write_u64(&mut out, self.height as u64);
+        write_u64(&mut out, self.seed);
+        write_u64(&mut out, self.generation);
+        match &self.boundary {
+            Boundary::Torus => out.push(0),
+            Boundary::Wall(cell) => {
+                out.push(1);
+                write_pos(&mut out, &cell.position());
+                cell.save(&mut out);
+            }
+            Boundary::Reflective => out.push(2),
+            Boundary::KleinBottle => out.push(3),
+        }
+        self.cells.iter().for_each(|cell| cell.save(&mut out));
+        write_u64(&mut out, self.changes.len() as u64);
+        self.changes.iter().for_each(|cell| {
+            write_pos(&mut out, &cell.position());
+            cell.save(&mut out);
+        });
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < MAGIC.len() + 2 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+        read_version(u16::from_le_bytes([bytes[4], bytes[5]]))?;
+
+        let input = &mut &bytes[MAGIC.len() + 2..];
+        let width = read_u64(input)? as usize;
+        let height = read_u64(input)? as usize;
+        let seed = read_u64(input)?;
+        let generation = read_u64(input)?;
+        let boundary = match read_u8(input)? {
+            0 => Boundary::Torus,
+            1 => {
+                let pos = read_pos(input)?;
+                Boundary::Wall(C::load(&pos, input)?)
+            }
+            2 => Boundary::Reflective,
+            3 => Boundary::KleinBottle,
+            b => return Err(SnapshotError::Invalid(format!("unknown boundary {}", b))),
+        };
+        let size = width
+            .checked_mul(height)
+            .ok_or_else(|| SnapshotError::Invalid(format!("a {}x{} board", width, height)))?;
+        // every cell takes at least one byte
+        if size > input.len() {
+            return Err(SnapshotError::UnexpectedEnd);
+        }
+        let cells = (0..size)
+            .map(|id| {
+                let pos = Pos2D::new((id % width) as i64, (id / width) as i64);
+                C::load(&pos, input)
+            })
+            .collect::<Result<Vec<C>, SnapshotError>>()?;
+        let changes_len = read_u64(input)?;
+        // and every change at least 17, with the position
+        if changes_len > input.len() as u64 / 17 {
+            return Err(SnapshotError::UnexpectedEnd);
+        }
+        let changes = (0..changes_len)
+            .map(|_| {
+                let pos = read_pos(input)?;
+                C::load(&pos, input)
+            })
+            .collect::<Result<Vec<C>, SnapshotError>>()?;
+        if !input.is_empty() {
+            return Err(SnapshotError::Invalid(format!(
+                "{} bytes left after the end",
+                input.len()
+            )));
+        }
+
+        Ok(Snapshot {
+            width,
+            height,
+            boundary,
+            seed,
+            generation,
+            cells,
+            changes,
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct Versioned<'a, C> {
+    version: u16,
+    #[serde(flatten)]
+    snapshot: &'a Snapshot<C>,
+}
+
+#[cfg(feature = "json")]
+impl<C: Serialize + for<'de> Deserialize<'de>> Snapshot<C> {
+    /// The snapshot as a JSON object, with the version of the format in the `version` field.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&Versioned {
+            version: SNAPSHOT_VERSION,
+            snapshot: self,
+        })
+        .unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|err| SnapshotError::Invalid(err.to_string()))?;
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or(SnapshotError::NotASnapshot)?;
+        read_version(version.min(u64::from(u16::MAX)) as u16)?;
+        serde_json::from_value(value).map_err(|err| SnapshotError::Invalid(err.to_string()))
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod snapshot_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::boundary::Boundary;
+    use crate::engine::life_rule::GenerationsRule;
+    use crate::engine::snapshot::*;
+    use crate::examples::forest_fire::ForestFire;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::examples::generations::Generations;
+    use crate::examples::langtons_ant::LangtonsAnt;
+    use crate::fields::Pos2D;
+
+    use spectral::prelude::*;
+
+    fn glider() -> Automaton<GameOfLife> {
+        let dead = GameOfLife::new(&Pos2D::new(-1, -1));
+        let mut auto = Automaton::<GameOfLife>::with_boundary(8, 6, Boundary::Wall(dead));
+        auto.set_seed(42);
+        for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, y)));
+        }
+        auto.step_n(3);
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(6, 4)));
+        auto
+    }
+
+    fn assert_same_future(mut loaded: Automaton<GameOfLife>, mut auto: Automaton<GameOfLife>) {
+        assert_that!(loaded.generation()).is_equal_to(auto.generation());
+        assert_that!(loaded.seed()).is_equal_to(auto.seed());
+        assert_that!(loaded.board()).is_equal_to(auto.board());
+        loaded.step_n(4);
+        auto.step_n(4);
+        assert_that!(loaded.board()).is_equal_to(auto.board());
+    }
+
+    #[test]
+    fn should_save_the_state_of_the_automaton() {
+        let auto = glider();
+        let snapshot = auto.snapshot();
+        assert_that!(snapshot.width).is_equal_to(8);
+        assert_that!(snapshot.height).is_equal_to(6);
+        assert_that!(snapshot.generation).is_equal_to(3);
+        assert_that!(snapshot.seed).is_equal_to(42);
+        assert_that!(snapshot.cells.len()).is_equal_to(48);
+        assert_that!(snapshot.cells[9].position()).is_equal_to(Pos2D::new(1, 1));
+        assert_that!(snapshot.changes).is_equal_to(vec![GameOfLife::new_life(&Pos2D::new(6, 4))]);
+    }
+
+    #[test]
+    fn should_load_a_snapshot_with_pending_changes() {
+        let auto = glider();
+        let loaded = Automaton::from_snapshot(auto.snapshot()).unwrap();
+        assert_same_future(loaded, auto);
+    }
+
+    #[test]
+    fn should_round_trip_through_bytes() {
+        let auto = glider();
+        let bytes = auto.snapshot().to_bytes();
+        assert_that!(bytes[..4].to_vec()).is_equal_to(b"GAIL".to_vec());
+
+        let snapshot = Snapshot::<GameOfLife>::from_bytes(&bytes).unwrap();
+        assert_that!(snapshot).is_equal_to(auto.snapshot());
+        assert_same_future(Automaton::from_snapshot(snapshot).unwrap(), auto);
+    }
+
+    #[test]
+    fn should_round_trip_cells_with_more_state() {
+        let mut ants = Automaton::<LangtonsAnt>::with_boundary(5, 5, Boundary::Reflective);
+        ants.add_change(&LangtonsAnt::new_ant(&Pos2D::new(2, 2)));
+        ants.step_n(3);
+        let snapshot = Snapshot::<LangtonsAnt>::from_bytes(&ants.snapshot().to_bytes()).unwrap();
+        assert_that!(snapshot).is_equal_to(ants.snapshot());
+
+        let rule = "345/2/4".parse::<GenerationsRule>().unwrap();
+        let board = Board::from_fn(4, 4, Boundary::KleinBottle, |pos| {
+            Generations::new_life(pos, rule)
+        });
+        let mut wars = Automaton::from_board(board);
+        wars.next();
+        let snapshot = Snapshot::<Generations>::from_bytes(&wars.snapshot().to_bytes()).unwrap();
+        assert_that!(snapshot).is_equal_to(wars.snapshot());
+        assert_that!(snapshot.cells[0].rule).is_equal_to(rule);
+
+        let mut forest = Automaton::<ForestFire>::new(6, 6);
+        forest.add_change(&ForestFire::new_tree(&Pos2D::new(3, 3)));
+        forest.next();
+        let snapshot = Snapshot::<ForestFire>::from_bytes(&forest.snapshot().to_bytes()).unwrap();
+        assert_that!(snapshot).is_equal_to(forest.snapshot());
+    }
+
+    #[test]
+    fn should_reject_other_versions() {
+        let mut bytes = glider().snapshot().to_bytes();
+        bytes[4] = 7;
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(&bytes)).is_err_containing(
+            SnapshotError::IncompatibleVersion {
+                found: 7,
+                supported: SNAPSHOT_VERSION,
+            },
+        );
+    }
+
+    #[test]
+    fn should_reject_broken_data() {
+        let bytes = glider().snapshot().to_bytes();
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(b"RIFF0000"))
+            .is_err_containing(SnapshotError::NotASnapshot);
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(
+            &bytes[..bytes.len() - 1]
+        ))
+        .is_err_containing(SnapshotError::UnexpectedEnd);
+
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(&longer)).is_err();
+
+        // the first cell is at 6 + 4 * 8 + 1 + 16 + 1
+        let mut not_a_bool = bytes;
+        not_a_bool[56] = 2;
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(&not_a_bool)).is_err();
+    }
+
+    #[test]
+    fn should_reject_snapshots_which_dont_fill_the_board() {
+        let mut snapshot = glider().snapshot();
+        snapshot.cells.swap(0, 1);
+        assert_that!(Automaton::from_snapshot(snapshot.clone()).is_err()).is_true();
+
+        snapshot.cells.pop();
+        assert_that!(Automaton::from_snapshot(snapshot.clone()).is_err()).is_true();
+
+        snapshot.width = usize::MAX;
+        snapshot.height = 2;
+        assert_that!(Automaton::from_snapshot(snapshot).is_err()).is_true();
+    }
+
+    fn with_size(bytes: &[u8], width: u64, height: u64) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        bytes[6..14].copy_from_slice(&width.to_le_bytes());
+        bytes[14..22].copy_from_slice(&height.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn should_reject_truncated_snapshots() {
+        let bytes = glider().snapshot().to_bytes();
+        for &len in &[6, 20, 38, 60, bytes.len() - 20] {
+            assert_that!(Snapshot::<GameOfLife>::from_bytes(&bytes[..len]))
+                .is_err_containing(SnapshotError::UnexpectedEnd);
+        }
+    }
+
+    #[test]
+    fn should_reject_oversized_headers() {
+        let bytes = glider().snapshot().to_bytes();
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(&with_size(
+            &bytes,
+            u64::MAX,
+            2
+        )))
+        .is_err();
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(&with_size(
+            &bytes,
+            1 << 32,
+            1 << 31
+        )))
+        .is_err_containing(SnapshotError::UnexpectedEnd);
+
+        // a huge number of changes
+        let mut changes = bytes[..bytes.len() - 25].to_vec();
+        changes.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert_that!(Snapshot::<GameOfLife>::from_bytes(&changes))
+            .is_err_containing(SnapshotError::UnexpectedEnd);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn should_round_trip_through_json() {
+        let auto = glider();
+        let json = auto.snapshot().to_json();
+        assert_that!(json.contains("\"version\": 1")).is_true();
+
+        let snapshot = Snapshot::<GameOfLife>::from_json(&json).unwrap();
+        assert_that!(snapshot).is_equal_to(auto.snapshot());
+        assert_same_future(Automaton::from_snapshot(snapshot).unwrap(), auto);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn should_write_rules_as_strings_in_json() {
+        let board = Board::from_fn(2, 2, Boundary::Torus, |pos| {
+            Generations::dead(pos, GenerationsRule::BRIANS_BRAIN)
+        });
+        let json = Automaton::from_board(board).snapshot().to_json();
+        assert_that!(json.contains("\"rule\": \"B2/S/C3\"")).is_true();
+        assert_that!(Snapshot::<Generations>::from_json(&json).is_ok()).is_true();
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn should_reject_other_versions_in_json() {
+        let json = glider()
+            .snapshot()
+            .to_json()
+            .replace("\"version\": 1", "\"version\": 2");
+        assert_that!(Snapshot::<GameOfLife>::from_json(&json)).is_err_containing(
+            SnapshotError::IncompatibleVersion {
+                found: 2,
+                supported: SNAPSHOT_VERSION,
+            },
+        );
+        assert_that!(Snapshot::<GameOfLife>::from_json("{\"width\": 3}"))
+            .is_err_containing(SnapshotError::NotASnapshot);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::engine::automaton::{AutomatonCell, Board};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// How a board settled: after `transient` generations it started repeating itself every `period`
+/// generations. A still life has the period 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stability {
+    pub transient: u64,
+    pub period: u64,
+}
+
+impl Stability {
+    #[inline]
+    pub fn is_still(&self) -> bool {
+        self.period == 1
+    }
+}
+
+fn hash_board<C: AutomatonCell + Hash>(board: &Board<C>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    board.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Finds out when a sequence of boards starts repeating itself, by remembering the hash of every
+/// board it's given. Two different boards may have the same hash, and then it reports a cycle
+/// which isn't there, but with 64-bit hashes it's very unlikely.
+///
+/// The cells are compared with each other, but not the generations and seeds of the boards, so if
+/// the cells use `Neighborhood::rng`, or the schedule of the automaton is random, a repeated board
+/// doesn't mean the next ones will repeat as well.
+#[derive(Clone)]
+pub struct CycleDetector<C: AutomatonCell> {
+    hash: fn(&Board<C>) -> u64,
+    // the hashes of the boards and the generations in which they were seen first
+    seen: HashMap<u64, u64>,
+    start: Option<u64>,
+    found: Option<Stability>,
+}
+
+impl<C: AutomatonCell + Hash> CycleDetector<C> {
+    pub fn new() -> Self {
+        CycleDetector {
+            hash: hash_board::<C>,
+            seen: HashMap::new(),
+            start: None,
+            found: None,
+        }
+    }
+}
+
+impl<C: AutomatonCell + Hash> Default for CycleDetector<C> {
+    fn default() -> Self {
+        CycleDetector::new()
+    }
+}
+
+impl<C: AutomatonCell> CycleDetector<C> {
+    /// Remembers `board`, and returns how the boards settled if it's been seen before, in an
+    /// earlier generation. The transient is counted from the first board given to the detector.
+    /// After a cycle is found, the next boards are ignored until `reset`.
+    pub fn observe(&mut self, board: &Board<C>) -> Option<Stability> {
+        if self.found.is_none() {
+            let generation = board.generation();
+            let start = *self.start.get_or_insert(generation);
+            let first = *self.seen.entry((self.hash)(board)).or_insert(generation);
+            if first < generation {
+                self.found = Some(Stability {
+                    transient: first - start,
+                    period: generation - first,
+                });
+            }
+        }
+        self.found
+    }
+
+    #[inline]
+    pub fn stability(&self) -> Option<Stability> {
+        self.found
+    }
+
+    /// Forgets all the boards, e.g. after the board was changed from the outside.
+    pub fn reset(&mut self) {
+        self.seen.clear();
+        self.start = None;
+        self.found = None;
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod stability_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::stability::*;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::fields::Pos2D;
+
+    use spectral::prelude::*;
+
+    fn life(width: usize, height: usize, cells: &[(i64, i64)]) -> Automaton<GameOfLife> {
+        let mut auto = Automaton::<GameOfLife>::new(width, height);
+        for &(x, y) in cells {
+            auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, y)));
+        }
+        auto
+    }
+
+    #[test]
+    fn should_find_still_life() {
+        let mut auto = life(6, 6, &[(1, 1), (2, 1), (1, 2), (2, 2)]);
+        let stability = auto.run_until_stable(10);
+        assert_that!(stability).is_equal_to(Some(Stability {
+            transient: 0,
+            period: 1,
+        }));
+        assert_that!(stability.unwrap().is_still()).is_true();
+        assert_that!(auto.board().generation()).is_equal_to(1);
+    }
+
+    #[test]
+    fn should_find_oscillator() {
+        let mut auto = life(6, 6, &[(1, 2), (2, 2), (3, 2)]);
+        assert_that!(auto.run_until_stable(10)).is_equal_to(Some(Stability {
+            transient: 0,
+            period: 2,
+        }));
+    }
+
+    #[test]
+    fn should_count_transient() {
+        // three cells in an L become a block
+        let mut auto = life(6, 6, &[(1, 1), (2, 1), (1, 2)]);
+        assert_that!(auto.run_until_stable(10)).is_equal_to(Some(Stability {
+            transient: 1,
+            period: 1,
+        }));
+    }
+
+    #[test]
+    fn should_find_glider_on_torus() {
+        // a glider moves by one cell diagonally every four generations
+        let mut auto = life(8, 8, &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        assert_that!(auto.run_until_stable(100)).is_equal_to(Some(Stability {
+            transient: 0,
+            period: 32,
+        }));
+    }
+
+    #[test]
+    fn should_give_up_after_max_generations() {
+        let mut auto = life(8, 8, &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        assert_that!(auto.run_until_stable(20)).is_none();
+        assert_that!(auto.board().generation()).is_equal_to(20);
+        // and carry on from there
+        assert_that!(auto.run_until_stable(20)).is_equal_to(Some(Stability {
+            transient: 0,
+            period: 32,
+        }));
+    }
+
+    #[test]
+    fn should_start_over_after_changes() {
+        let mut auto = life(6, 6, &[(1, 1), (2, 1), (1, 2), (2, 2)]);
+        auto.set_cycle_detection(true);
+        auto.next();
+        auto.next();
+        assert_that!(auto.stability()).is_some();
+
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(4, 4)));
+        auto.next();
+        assert_that!(auto.stability()).is_none();
+        auto.next();
+        assert_that!(auto.stability()).is_some();
+    }
+
+    #[test]
+    fn should_detect_cycles_in_boards() {
+        let mut detector = CycleDetector::<GameOfLife>::new();
+        let mut board = Board::<GameOfLife>::new(6, 6).copy_and_update(&[
+            GameOfLife::new_life(&Pos2D::new(2, 1)),
+            GameOfLife::new_life(&Pos2D::new(2, 2)),
+            GameOfLife::new_life(&Pos2D::new(2, 3)),
+        ]);
+        assert_that!(detector.observe(&board)).is_none();
+        // the same board in the same generation isn't a cycle
+        assert_that!(detector.observe(&board)).is_none();
+        board = board.update();
+        assert_that!(detector.observe(&board)).is_none();
+        board = board.update();
+        assert_that!(detector.observe(&board)).is_equal_to(Some(Stability {
+            transient: 0,
+            period: 2,
+        }));
+
+        detector.reset();
+        assert_that!(detector.stability()).is_none();
+    }
+}
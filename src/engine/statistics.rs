@@ -0,0 +1,208 @@
+use crate::engine::automaton::{AutomatonCell, Board};
+
+use std::any::Any;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What `Statistics` recorded in one generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub generation: u64,
+    /// The number of cells in each category, in the order of `Statistics::categories`.
+    /// Categories which appeared only in later generations are missing at the end.
+    pub counts: Vec<usize>,
+    /// The cells which changed since the previous sample, including the ones from
+    /// `Automaton::add_change`.
+    pub changed: usize,
+    /// How long it took to compute the generation.
+    pub update_time: Duration,
+}
+
+impl Sample {
+    /// The number of cells in the category with the given index.
+    #[inline]
+    pub fn count(&self, index: usize) -> usize {
+        self.counts.get(index).copied().unwrap_or(0)
+    }
+}
+
+/// Counts the cells of an automaton in categories chosen by the user, e.g. "alive" and "dead",
+/// generation after generation (see `Automaton::set_statistics`). The series can be written
+/// as CSV, with one row per sample.
+///
+/// Besides `Automaton::next`, a sample is recorded by `Automaton::transform`, with the same
+/// generation as the one before it. Going back with `Automaton::rewind_to` or `step_back` forgets
+/// the samples of the generations it goes back over, and records the restored board instead.
+/// Changes from `Automaton::apply_changes` are counted in the sample of the next generation.
+///
+/// The categories can be of any type `K` which can be hashed and printed, e.g. an enum
+/// or a `&'static str`.
+#[derive(Clone)]
+pub struct Statistics<C: AutomatonCell, K = String> {
+    categorize: Arc<dyn Fn(&C) -> K + Send + Sync>,
+    categories: Vec<K>,
+    indices: HashMap<K, usize>,
+    samples: Vec<Sample>,
+}
+
+impl<C: AutomatonCell, K: Hash + Eq + Clone + Display> Statistics<C, K> {
+    /// `categorize` is called for every cell in every generation, e.g.
+    /// `|c: &LangtonsAnt| if c.dir.is_some() { "ant" } else { "no ant" }`,
+    /// or `|c| c.cell_to_rectangle().color().to_string()` to count the colours on the screen.
+    pub fn new(categorize: impl Fn(&C) -> K + Send + Sync + 'static) -> Self {
+        Statistics {
+            categorize: Arc::new(categorize),
+            categories: Vec::new(),
+            indices: HashMap::new(),
+            samples: Vec::new(),
+        }
+    }
+
+    /// The categories in the order in which they first appeared.
+    #[inline]
+    pub fn categories(&self) -> &[K] {
+        &self.categories
+    }
+
+    #[inline]
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// The number of cells in `category` in the last sample of the given generation, or `None`
+    /// if it wasn't recorded.
+    pub fn count<Q>(&self, generation: u64, category: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let sample = self.samples.iter().rfind(|s| s.generation == generation)?;
+        Some(
+            self.indices
+                .get(category)
+                .map_or(0, |&index| sample.count(index)),
+        )
+    }
+
+    /// Forgets the samples, but not the categories.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Forgets the samples of `generation` and the later ones.
+    pub fn truncate(&mut self, generation: u64) {
+        self.samples.retain(|s| s.generation < generation);
+    }
+
+    /// Adds a sample of `board`. The automaton does this after every change of its board.
+    pub fn record(&mut self, board: &Board<C>, changed: usize, update_time: Duration) {
+        let mut counts = vec![0; self.categories.len()];
+        for (_, cell) in board.iter() {
+            let category = (self.categorize)(cell);
+            let index = match self.indices.get(&category) {
+                Some(&index) => index,
+                None => {
+                    let index = self.categories.len();
+                    self.indices.insert(category.clone(), index);
+                    self.categories.push(category);
+                    counts.push(0);
+                    index
+                }
+            };
+            counts[index] += 1;
+        }
+        self.samples.push(Sample {
+            generation: board.generation(),
+            counts,
+            changed,
+            update_time,
+        });
+    }
+
+    /// Writes the samples as CSV: a header, and then a row per sample with the generation,
+    /// the number of changed cells, the update time in microseconds, and the count of every
+    /// category.
+    pub fn write_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "generation,changed,update_time_us")?;
+        for category in &self.categories {
+            write!(out, ",{}", csv_field(&category.to_string()))?;
+        }
+        writeln!(out)?;
+
+        for sample in &self.samples {
+            write!(
+                out,
+                "{},{},{}",
+                sample.generation,
+                sample.changed,
+                sample.update_time.as_micros()
+            )?;
+            for index in 0..self.categories.len() {
+                write!(out, ",{}", sample.count(index))?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = Vec::new();
+        self.write_csv(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+}
+
+// Quotes the field if it has a comma, a quote or a line break in it.
+fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// What the automaton needs from its statistics, whatever the type of their categories.
+pub(crate) trait Recorder<C: AutomatonCell>: Send + Sync {
+    fn record(&mut self, board: &Board<C>, changed: usize, update_time: Duration);
+    fn truncate(&mut self, generation: u64);
+    fn clone_box(&self) -> Box<dyn Recorder<C>>;
+    fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<C, K> Recorder<C> for Statistics<C, K>
+where
+    C: AutomatonCell + 'static,
+    K: Hash + Eq + Clone + Display + Send + Sync + 'static,
+{
+    fn record(&mut self, board: &Board<C>, changed: usize, update_time: Duration) {
+        Statistics::record(self, board, changed, update_time);
+    }
+
+    fn truncate(&mut self, generation: u64) {
+        Statistics::truncate(self, generation);
+    }
+
+    fn clone_box(&self) -> Box<dyn Recorder<C>> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl<C: AutomatonCell> Clone for Box<dyn Recorder<C>> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
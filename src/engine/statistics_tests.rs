@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod statistics_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::statistics::*;
+    use crate::engine::test_helpers::{assert_send_sync, blinker};
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::examples::langtons_ant::LangtonsAnt;
+    use crate::fields::Pos2D;
+
+    use spectral::prelude::*;
+
+    fn life(cell: &GameOfLife) -> &'static str {
+        if cell.life {
+            "alive"
+        } else {
+            "dead"
+        }
+    }
+
+    #[test]
+    fn should_count_cells_in_categories() {
+        let mut auto = blinker(5);
+        auto.set_statistics(Statistics::new(life));
+        auto.step_n(2);
+
+        let stats = auto.statistics::<&str>().unwrap();
+        assert_that!(stats.categories().to_vec()).is_equal_to(vec!["dead", "alive"]);
+        let samples = stats.samples();
+        assert_that!(samples.len()).is_equal_to(3);
+        assert_that!(samples.iter().map(|s| s.generation).collect::<Vec<u64>>())
+            .is_equal_to(vec![0, 1, 2]);
+        assert_that!(samples[0].counts.clone()).is_equal_to(vec![25]);
+        assert_that!(samples[1].counts.clone()).is_equal_to(vec![22, 3]);
+        assert_that!(stats.count(2, "alive")).is_equal_to(Some(3));
+        assert_that!(stats.count(0, "alive")).is_equal_to(Some(0));
+        assert_that!(stats.count(3, "alive")).is_none();
+    }
+
+    #[test]
+    fn should_count_changed_cells() {
+        let mut auto = blinker(5);
+        auto.set_statistics(Statistics::new(life));
+        auto.step_n(2);
+
+        let changed: Vec<usize> = auto
+            .statistics::<&str>()
+            .unwrap()
+            .samples()
+            .iter()
+            .map(|s| s.changed)
+            .collect();
+        // only the cells which are different than before the changes were applied
+        assert_that!(changed).is_equal_to(vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn should_count_applied_changes_in_the_next_sample() {
+        let mut auto = blinker(5);
+        auto.set_statistics(Statistics::new(life));
+        auto.apply_changes();
+        auto.next();
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(0, 0)));
+        auto.apply_changes();
+        // changed back before the next sample
+        auto.add_change(&GameOfLife::new(&Pos2D::new(0, 0)));
+        auto.apply_changes();
+        auto.next();
+
+        let changed: Vec<usize> = auto
+            .statistics::<&str>()
+            .unwrap()
+            .samples()
+            .iter()
+            .map(|s| s.changed)
+            .collect();
+        assert_that!(changed).is_equal_to(vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn should_record_transformations_and_rewinds() {
+        let mut auto = blinker(5);
+        auto.set_history_length(10);
+        auto.set_statistics(Statistics::new(life));
+        auto.step_n(3);
+        auto.transform(|board| board.copy_and_update_one(&GameOfLife::new_life(&Pos2D::new(0, 0))));
+
+        let generations = |auto: &Automaton<GameOfLife>| -> Vec<u64> {
+            let samples = auto.statistics::<&str>().unwrap().samples();
+            samples.iter().map(|s| s.generation).collect()
+        };
+        assert_that!(generations(&auto)).is_equal_to(vec![0, 1, 2, 3, 3]);
+        assert_that!(auto.statistics::<&str>().unwrap().count(3, "alive")).is_equal_to(Some(4));
+
+        auto.step_back(2);
+        assert_that!(generations(&auto)).is_equal_to(vec![0, 1]);
+        assert_that!(auto.statistics::<&str>().unwrap().count(1, "alive")).is_equal_to(Some(3));
+    }
+
+    #[test]
+    fn should_keep_the_automaton_send_and_sync() {
+        assert_send_sync::<Statistics<GameOfLife>>();
+        assert_send_sync::<Automaton<GameOfLife>>();
+    }
+
+    #[test]
+    fn should_stop_recording_when_detached() {
+        let mut auto = blinker(5);
+        auto.set_statistics(Statistics::new(life));
+        auto.next();
+        let stats = auto.take_statistics::<&str>().unwrap();
+        auto.next();
+        assert_that!(stats.samples().len()).is_equal_to(2);
+        assert_that!(auto.statistics::<&str>().is_none()).is_true();
+    }
+
+    #[test]
+    fn should_keep_statistics_of_other_categories() {
+        let mut auto = blinker(5);
+        auto.set_statistics(Statistics::new(life));
+        assert_that!(auto.statistics::<String>().is_none()).is_true();
+        assert_that!(auto.take_statistics::<String>().is_none()).is_true();
+        auto.next();
+        assert_that!(auto.statistics::<&str>().unwrap().samples().len()).is_equal_to(2);
+        auto.remove_statistics();
+        assert_that!(auto.statistics::<&str>().is_none()).is_true();
+    }
+
+    #[test]
+    fn should_export_csv() {
+        let mut ants = Automaton::<LangtonsAnt>::new(4, 4);
+        ants.add_change(&LangtonsAnt::new_ant(&Pos2D::new(1, 1)));
+        ants.set_statistics(Statistics::new(|c: &LangtonsAnt| {
+            if c.dir.is_some() {
+                "ant"
+            } else {
+                "no ant, \"empty\""
+            }
+        }));
+        ants.next();
+
+        let csv = ants.statistics::<&str>().unwrap().to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_that!(lines.len()).is_equal_to(3);
+        assert_that!(lines[0])
+            .is_equal_to("generation,changed,update_time_us,\"no ant, \"\"empty\"\"\",ant");
+        assert_that!(lines[1]).is_equal_to("0,0,0,16,0");
+        // the ant leaves its cell as it was before it was added, and moves to the next one
+        assert_that!(lines[2].starts_with("1,1,")).is_true();
+        assert_that!(lines[2].ends_with(",15,1")).is_true();
+    }
+}
@@ -0,0 +1,154 @@
+use crate::engine::automaton::{AutomatonCell, Neighborhood};
+use crate::fields::{Dir2D, Pos2D};
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::slice;
+
+/// A neighbourhood of any shape, given as offsets relative to the cell. A template is validated
+/// when it's created, so it's best to create it once, e.g. in `lazy_static!`, and then use it in
+/// `AutomatonCell::update` through `Template::cells`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    offsets: Vec<Pos2D>,
+    radius: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    Empty,
+    Duplicate(Pos2D),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::Empty => write!(f, "the template has no offsets"),
+            TemplateError::Duplicate(pos) => write!(f, "the offset {} appears more than once", pos),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+impl Template {
+    /// The offsets are kept in the given order. `(0, 0)`, the cell itself, is allowed.
+    pub fn new(offsets: &[Pos2D]) -> Result<Template, TemplateError> {
+        if offsets.is_empty() {
+            return Err(TemplateError::Empty);
+        }
+        let mut set = HashSet::with_capacity(offsets.len());
+        if let Some(pos) = offsets.iter().find(|&pos| !set.insert(*pos)) {
+            return Err(TemplateError::Duplicate(*pos));
+        }
+
+        let radius = offsets
+            .iter()
+            .map(|p| p.x.abs().max(p.y.abs()) as usize)
+            .max()
+            .unwrap();
+        Ok(Template {
+            offsets: offsets.to_vec(),
+            radius,
+        })
+    }
+
+    fn from_filter(radius: usize, f: impl Fn(i64, i64) -> bool) -> Result<Template, TemplateError> {
+        let r = radius as i64;
+        let offsets: Vec<Pos2D> = (-r..=r)
+            .flat_map(|y| (-r..=r).map(move |x| Pos2D::new(x, y)))
+            .filter(|p| (p.x != 0 || p.y != 0) && f(p.x, p.y))
+            .collect();
+        Template::new(&offsets)
+    }
+
+    /// The same cells as `Neighborhood::moore_radius`.
+    pub fn moore(radius: usize) -> Result<Template, TemplateError> {
+        Template::from_filter(radius, |_, _| true)
+    }
+
+    /// The same cells as `Neighborhood::neumann_radius`.
+    pub fn neumann(radius: usize) -> Result<Template, TemplateError> {
+        Template::from_filter(radius, |x, y| (x.abs() + y.abs()) as usize <= radius)
+    }
+
+    /// The eight squares a chess knight can jump to.
+    pub fn knight() -> Template {
+        Template::from_filter(2, |x, y| x.abs() + y.abs() == 3).unwrap()
+    }
+
+    /// The six neighbours of a hex in axial coordinates (see `fields::Hex`), used directly on
+    /// a square grid: the Moore neighbourhood without the up-left and down-right corners.
+    pub fn hexish() -> Template {
+        Template::from_filter(1, |x, y| x != y).unwrap()
+    }
+
+    /// What an agent looking in `dir` sees: a 90 degree cone, up to `length` cells ahead.
+    pub fn cone(dir: Dir2D, length: usize) -> Result<Template, TemplateError> {
+        let v = Pos2D::new(0, 0).move_by_one(dir);
+        Template::from_filter(length, |x, y| {
+            if v.x != 0 && v.y != 0 {
+                x * v.x >= 0 && y * v.y >= 0
+            } else {
+                x * v.x + y * v.y >= (x * v.y - y * v.x).abs()
+            }
+        })
+    }
+
+    #[inline]
+    pub fn offsets(&self) -> &[Pos2D] {
+        &self.offsets
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// How far the furthest offset reaches in any direction. A cell using the template
+    /// should return it from `AutomatonCell::radius`.
+    #[inline]
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    /// The offsets and the cells they point to from `pos`, in the order of the offsets.
+    pub fn cells<'a, C: AutomatonCell>(
+        &'a self,
+        neighborhood: &'a Neighborhood<C>,
+        pos: &Pos2D,
+    ) -> TemplateCells<'a, C> {
+        TemplateCells {
+            neighborhood,
+            pos: *pos,
+            offsets: self.offsets.iter(),
+        }
+    }
+}
+
+pub struct TemplateCells<'a, C: AutomatonCell> {
+    neighborhood: &'a Neighborhood<C>,
+    pos: Pos2D,
+    offsets: slice::Iter<'a, Pos2D>,
+}
+
+impl<'a, C: AutomatonCell> Iterator for TemplateCells<'a, C> {
+    type Item = (Pos2D, &'a C);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.offsets.next().map(|offset| {
+            let pos = Pos2D::new(self.pos.x + offset.x, self.pos.y + offset.y);
+            (*offset, self.neighborhood.find_cell(&pos))
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
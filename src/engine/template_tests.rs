@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod template_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::template::*;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::fields::{Dir2D, Pos2D};
+
+    use spectral::prelude::*;
+
+    fn offsets(v: &[(i64, i64)]) -> Vec<Pos2D> {
+        v.iter().map(|&(x, y)| Pos2D::new(x, y)).collect()
+    }
+
+    #[test]
+    fn should_validate_offsets() {
+        assert_that!(Template::new(&[])).is_equal_to(Err(TemplateError::Empty));
+        assert_that!(Template::new(&offsets(&[(1, 0), (0, 1), (1, 0)])))
+            .is_equal_to(Err(TemplateError::Duplicate(Pos2D::new(1, 0))));
+        assert_that!(Template::moore(0)).is_equal_to(Err(TemplateError::Empty));
+
+        let template = Template::new(&offsets(&[(0, 0), (3, -1), (-2, 2)])).unwrap();
+        assert_that!(template.len()).is_equal_to(3);
+        assert_that!(template.radius()).is_equal_to(3);
+    }
+
+    #[test]
+    fn should_make_standard_shapes() {
+        assert_that!(Template::moore(2).unwrap().len()).is_equal_to(24);
+        assert_that!(Template::neumann(2).unwrap().len()).is_equal_to(12);
+        assert_that!(Template::knight().len()).is_equal_to(8);
+        assert_that!(Template::knight().radius()).is_equal_to(2);
+        assert_that!(Template::hexish().offsets().to_vec()).is_equal_to(offsets(&[
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+        ]));
+    }
+
+    #[test]
+    fn should_make_cones() {
+        assert_that!(Template::cone(Dir2D::Up, 2).unwrap().offsets().to_vec()).is_equal_to(
+            offsets(&[
+                (-2, -2),
+                (-1, -2),
+                (0, -2),
+                (1, -2),
+                (2, -2),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ]),
+        );
+        assert_that!(Template::cone(Dir2D::Right, 1).unwrap().offsets().to_vec())
+            .is_equal_to(offsets(&[(1, -1), (1, 0), (1, 1)]));
+        assert_that!(Template::cone(Dir2D::DownLeft, 1)
+            .unwrap()
+            .offsets()
+            .to_vec())
+        .is_equal_to(offsets(&[(-1, 0), (-1, 1), (0, 1)]));
+    }
+
+    #[test]
+    fn should_match_radius_neighbourhoods() {
+        let board = Board::<GameOfLife>::new(10, 10);
+        let pos = Pos2D::new(1, 8);
+        let from_template =
+            |t: Template| -> Vec<Pos2D> { t.cells(&board, &pos).map(|(_, c)| c.pos).collect() };
+        fn from_board<'a>(cells: impl Iterator<Item = &'a GameOfLife>) -> Vec<Pos2D> {
+            cells.map(|c| c.pos).collect()
+        }
+
+        assert_that!(from_template(Template::moore(3).unwrap()))
+            .is_equal_to(from_board(board.moore_radius(&pos, 3)));
+        assert_that!(from_template(Template::neumann(3).unwrap()))
+            .is_equal_to(from_board(board.neumann_radius(&pos, 3)));
+    }
+
+    lazy_static! {
+        static ref KNIGHT: Template = Template::knight();
+    }
+
+    // A cell which comes to life if a knight's move away there's exactly one living cell.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Knight {
+        life: bool,
+        pos: Pos2D,
+    }
+
+    impl AutomatonCell for Knight {
+        fn update(&self, neighborhood: &Neighborhood<Self>) -> Self {
+            let n = KNIGHT
+                .cells(neighborhood, &self.pos)
+                .filter(|(_, c)| c.life)
+                .count();
+            Knight {
+                life: n == 1,
+                pos: self.pos,
+            }
+        }
+
+        fn position(&self) -> Pos2D {
+            self.pos
+        }
+
+        fn new(pos: &Pos2D) -> Self {
+            Knight {
+                life: false,
+                pos: *pos,
+            }
+        }
+
+        fn radius(&self) -> usize {
+            KNIGHT.radius()
+        }
+    }
+
+    #[test]
+    fn should_update_with_template() {
+        let mut board = Board::<Knight>::new(10, 10).copy_and_update_one(&Knight {
+            life: true,
+            pos: Pos2D::new(5, 5),
+        });
+        board = board.update();
+
+        let alive: Vec<Pos2D> = board
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect();
+        assert_that!(alive).is_equal_to(offsets(&[
+            (4, 3),
+            (6, 3),
+            (3, 4),
+            (7, 4),
+            (3, 6),
+            (7, 6),
+            (4, 7),
+            (6, 7),
+        ]));
+    }
+}
@@ -0,0 +1,17 @@
+//! Fixtures and checks shared by the tests of the engine.
+use crate::engine::automaton::Automaton;
+use crate::examples::game_of_life::GameOfLife;
+use crate::fields::Pos2D;
+
+/// A `size` x `size` automaton with a horizontal blinker at (1, 2), (2, 2) and (3, 2), added
+/// with `add_change`, so it's on the board only after `apply_changes` or `next`.
+pub fn blinker(size: usize) -> Automaton<GameOfLife> {
+    let mut auto = Automaton::<GameOfLife>::new(size, size);
+    for x in 1..4 {
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, 2)));
+    }
+    auto
+}
+
+/// Compiles only if `T` can be sent to and shared between threads.
+pub fn assert_send_sync<T: Send + Sync>() {}
@@ -0,0 +1,102 @@
+use crate::engine::automaton::*;
+use crate::engine::snapshot::{self, SnapshotCell, SnapshotError};
+use crate::fields::Pos2D;
+
+use rand::Rng;
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub enum Tree {
+    Empty,
+    Growing,
+    Burning,
+}
+
+/// The Drossel-Schwabl forest-fire model: trees grow on empty cells, catch fire from burning
+/// neighbours or are struck by lightning, and burn down in one generation.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct ForestFire {
+    pub tree: Tree,
+    pub pos: Pos2D,
+}
+
+impl ForestFire {
+    /// The probability that a tree grows on an empty cell.
+    pub const GROWTH: f64 = 0.01;
+    /// The probability that a tree is struck by lightning.
+    pub const LIGHTNING: f64 = 0.000_05;
+
+    pub fn new_tree(pos: &Pos2D) -> Self {
+        ForestFire {
+            tree: Tree::Growing,
+            pos: *pos,
+        }
+    }
+
+    fn update_tree(&self, neighborhood: &Neighborhood<Self>) -> Tree {
+        match self.tree {
+            Tree::Burning => Tree::Empty,
+            Tree::Empty if neighborhood.rng(&self.pos).gen::<f64>() < ForestFire::GROWTH => {
+                Tree::Growing
+            }
+            Tree::Empty => Tree::Empty,
+            Tree::Growing => {
+                let fire = neighborhood
+                    .moore(&self.pos)
+                    .values()
+                    .any(|c| c.tree == Tree::Burning);
+                if fire || neighborhood.rng(&self.pos).gen::<f64>() < ForestFire::LIGHTNING {
+                    Tree::Burning
+                } else {
+                    Tree::Growing
+                }
+            }
+        }
+    }
+}
+
+impl AutomatonCell for ForestFire {
+    fn update(&self, neighborhood: &Neighborhood<Self>) -> Self {
+        ForestFire {
+            tree: self.update_tree(neighborhood),
+            pos: self.pos,
+        }
+    }
+
+    fn position(&self) -> Pos2D {
+        self.pos
+    }
+
+    fn new(pos: &Pos2D) -> Self {
+        ForestFire {
+            tree: Tree::Empty,
+            pos: *pos,
+        }
+    }
+}
+
+impl SnapshotCell for ForestFire {
+    fn save(&self, out: &mut Vec<u8>) {
+        out.push(self.tree as u8);
+    }
+
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError> {
+        let tree = match snapshot::read_u8(input)? {
+            0 => Tree::Empty,
+            1 => Tree::Growing,
+            2 => Tree::Burning,
+            n => return Err(SnapshotError::Invalid(format!("unknown tree {}", n))),
+        };
+        Ok(ForestFire { tree, pos: *pos })
+    }
+}
+
+impl fmt::Debug for ForestFire {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ForestFire({:?} -> {:?})", self.pos, self.tree)
+    }
+}
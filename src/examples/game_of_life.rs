@@ -1,9 +1,13 @@
 use crate::engine::automaton::*;
+use crate::engine::snapshot::{self, SnapshotCell, SnapshotError};
 use crate::fields::Pos2D;
 
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct GameOfLife {
     pub life: bool,
     pub pos: Pos2D,
@@ -49,6 +53,23 @@ impl AutomatonCell for GameOfLife {
             pos: *pos,
         }
     }
+
+    fn is_quiescent(&self) -> bool {
+        !self.life
+    }
+}
+
+impl SnapshotCell for GameOfLife {
+    fn save(&self, out: &mut Vec<u8>) {
+        out.push(self.life as u8);
+    }
+
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError> {
+        Ok(GameOfLife {
+            life: snapshot::read_bool(input)?,
+            pos: *pos,
+        })
+    }
 }
 
 impl fmt::Debug for GameOfLife {
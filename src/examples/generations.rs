@@ -0,0 +1,117 @@
+use crate::engine::automaton::*;
+use crate::engine::life_rule::{Counting, GenerationsRule};
+use crate::engine::snapshot::{self, SnapshotCell, SnapshotError};
+use crate::fields::Pos2D;
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A multi-state cell following a "Generations" rule, e.g. Brian's Brain (`/2/3`) or
+/// Star Wars (`345/2/4`). As with `LifeLike`, every cell carries its rule, so make the board with
+/// `Board::from_fn` and `Generations::dead`; `AutomatonCell::new` makes a dead Brian's Brain cell.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Generations {
+    /// 0 is dead, 1 is alive, and the higher states are refractory: the cell is dying.
+    pub state: u8,
+    pub pos: Pos2D,
+    pub rule: GenerationsRule,
+}
+
+impl Generations {
+    pub fn new_life(pos: &Pos2D, rule: GenerationsRule) -> Self {
+        Generations {
+            state: 1,
+            pos: *pos,
+            rule,
+        }
+    }
+
+    pub fn dead(pos: &Pos2D, rule: GenerationsRule) -> Self {
+        Generations {
+            state: 0,
+            pos: *pos,
+            rule,
+        }
+    }
+
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.state == 1
+    }
+
+    fn update_state(&self, neighborhood: &Neighborhood<Self>) -> u8 {
+        let n = match self.rule.rule().counting() {
+            Counting::Moore => neighborhood
+                .moore(&self.pos)
+                .values()
+                .filter(|c| c.is_alive())
+                .count(),
+            Counting::Neumann => neighborhood
+                .neumann(&self.pos)
+                .values()
+                .filter(|c| c.is_alive())
+                .count(),
+        };
+        self.rule.next(self.state, n)
+    }
+}
+
+impl AutomatonCell for Generations {
+    fn update(&self, neighborhood: &Neighborhood<Self>) -> Self {
+        Generations {
+            state: self.update_state(neighborhood),
+            ..*self
+        }
+    }
+
+    fn position(&self) -> Pos2D {
+        self.pos
+    }
+
+    fn new(pos: &Pos2D) -> Self {
+        Generations::dead(pos, GenerationsRule::BRIANS_BRAIN)
+    }
+
+    fn is_quiescent(&self) -> bool {
+        self.state == 0 && !self.rule.rule().born(0)
+    }
+}
+
+impl SnapshotCell for Generations {
+    /// The rule is saved as its string, e.g. "B2/S/C3".
+    fn save(&self, out: &mut Vec<u8>) {
+        out.push(self.state);
+        snapshot::write_string(out, &self.rule.to_string());
+    }
+
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError> {
+        let state = snapshot::read_u8(input)?;
+        let rule = snapshot::read_string(input)?
+            .parse::<GenerationsRule>()
+            .map_err(|err| SnapshotError::Invalid(err.to_string()))?;
+        if state >= rule.states() {
+            return Err(SnapshotError::Invalid(format!(
+                "state {} in a rule with {} states",
+                state,
+                rule.states()
+            )));
+        }
+        Ok(Generations {
+            state,
+            pos: *pos,
+            rule,
+        })
+    }
+}
+
+impl fmt::Debug for Generations {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Generations({:?} -> {}, {})",
+            self.pos, self.state, self.rule
+        )
+    }
+}
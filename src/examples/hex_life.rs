@@ -0,0 +1,81 @@
+use crate::engine::automaton::*;
+use crate::engine::snapshot::{self, SnapshotCell, SnapshotError};
+use crate::fields::Pos2D;
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Game of Life on a hex grid, with the B2/S34 rule: a dead cell comes to life with exactly two
+/// living neighbours, and a living one survives with three or four.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct HexLife {
+    pub life: bool,
+    pub pos: Pos2D,
+}
+
+impl HexLife {
+    pub fn new_life(pos: &Pos2D) -> Self {
+        HexLife {
+            life: true,
+            pos: *pos,
+        }
+    }
+
+    fn update_life(&self, neighborhood: &Neighborhood<Self>) -> bool {
+        match neighborhood
+            .hex(&self.pos)
+            .values()
+            .filter(|c| c.life)
+            .count()
+        {
+            2 if !self.life => true,
+            n if self.life && !(3..=4).contains(&n) => false,
+            _ => self.life,
+        }
+    }
+}
+
+impl AutomatonCell for HexLife {
+    fn update(&self, neighborhood: &Neighborhood<Self>) -> Self {
+        HexLife {
+            life: self.update_life(neighborhood),
+            pos: self.pos,
+        }
+    }
+
+    fn position(&self) -> Pos2D {
+        self.pos
+    }
+
+    fn new(pos: &Pos2D) -> Self {
+        HexLife {
+            life: false,
+            pos: *pos,
+        }
+    }
+
+    fn is_quiescent(&self) -> bool {
+        !self.life
+    }
+}
+
+impl SnapshotCell for HexLife {
+    fn save(&self, out: &mut Vec<u8>) {
+        out.push(self.life as u8);
+    }
+
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError> {
+        Ok(HexLife {
+            life: snapshot::read_bool(input)?,
+            pos: *pos,
+        })
+    }
+}
+
+impl fmt::Debug for HexLife {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HexLife({:?} -> {:?})", self.pos, self.life)
+    }
+}
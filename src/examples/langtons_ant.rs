@@ -1,9 +1,13 @@
 use crate::engine::automaton::*;
-use crate::fields::{Dir2D, Pos2D};
+use crate::engine::snapshot::{self, SnapshotCell, SnapshotError};
+use crate::fields::{Dir2D, Pos2D, DIRS8};
 
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct LangtonsAnt {
     pub color: bool,
     pub dir: Option<Dir2D>,
@@ -64,6 +68,32 @@ impl AutomatonCell for LangtonsAnt {
             pos: *pos,
         }
     }
+
+    fn is_quiescent(&self) -> bool {
+        self.dir.is_none()
+    }
+}
+
+impl SnapshotCell for LangtonsAnt {
+    /// The color, and then 0 without an ant, or the index of its direction plus one.
+    fn save(&self, out: &mut Vec<u8>) {
+        out.push(self.color as u8);
+        out.push(self.dir.map_or(0, |dir| dir.index() as u8 + 1));
+    }
+
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError> {
+        let color = snapshot::read_bool(input)?;
+        let dir = match snapshot::read_u8(input)? {
+            0 => None,
+            n if n as usize <= DIRS8.len() => Some(DIRS8[n as usize - 1]),
+            n => return Err(SnapshotError::Invalid(format!("unknown direction {}", n))),
+        };
+        Ok(LangtonsAnt {
+            color,
+            dir,
+            pos: *pos,
+        })
+    }
 }
 
 impl fmt::Debug for LangtonsAnt {
@@ -0,0 +1,74 @@
+use crate::engine::automaton::*;
+use crate::engine::life_rule::{Counting, LtlRule};
+use crate::fields::Pos2D;
+
+use std::fmt;
+
+/// A two-state cell following a Larger-than-Life rule, e.g. Bugs. As with `LifeLike`, every cell
+/// carries its rule, so make the board with `Board::from_fn` and `LargerThanLife::dead`;
+/// `AutomatonCell::new` makes a dead Bugs cell.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LargerThanLife {
+    pub life: bool,
+    pub pos: Pos2D,
+    pub rule: LtlRule,
+}
+
+impl LargerThanLife {
+    pub fn new_life(pos: &Pos2D, rule: LtlRule) -> Self {
+        LargerThanLife {
+            life: true,
+            pos: *pos,
+            rule,
+        }
+    }
+
+    pub fn dead(pos: &Pos2D, rule: LtlRule) -> Self {
+        LargerThanLife {
+            life: false,
+            pos: *pos,
+            rule,
+        }
+    }
+
+    fn update_life(&self, neighborhood: &Neighborhood<Self>) -> bool {
+        let neighbours = match self.rule.counting {
+            Counting::Moore => neighborhood.moore_radius(&self.pos, self.rule.range),
+            Counting::Neumann => neighborhood.neumann_radius(&self.pos, self.rule.range),
+        };
+        let n = neighbours.filter(|c| c.life).count();
+        let me = if self.rule.middle && self.life { 1 } else { 0 };
+        self.rule.next(self.life, n + me)
+    }
+}
+
+impl AutomatonCell for LargerThanLife {
+    fn update(&self, neighborhood: &Neighborhood<Self>) -> Self {
+        LargerThanLife {
+            life: self.update_life(neighborhood),
+            ..*self
+        }
+    }
+
+    fn position(&self) -> Pos2D {
+        self.pos
+    }
+
+    fn new(pos: &Pos2D) -> Self {
+        LargerThanLife::dead(pos, LtlRule::BUGS)
+    }
+
+    fn is_quiescent(&self) -> bool {
+        !self.life && self.rule.birth.0 > 0
+    }
+
+    fn radius(&self) -> usize {
+        self.rule.range
+    }
+}
+
+impl fmt::Debug for LargerThanLife {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LargerThanLife({:?} -> {:?})", self.pos, self.life)
+    }
+}
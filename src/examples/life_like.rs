@@ -0,0 +1,105 @@
+use crate::engine::automaton::*;
+use crate::engine::life_rule::{Counting, LifeRule};
+use crate::engine::snapshot::{self, SnapshotCell, SnapshotError};
+use crate::fields::Pos2D;
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A two-state cell following any Life-like rule, e.g. HighLife (`B36/S23`), Seeds (`B2/S`) or
+/// Day & Night (`B3678/S34678`). Every cell carries its rule, so make the board with
+/// `Board::from_fn` and `LifeLike::dead`; `AutomatonCell::new` makes a dead Conway cell.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct LifeLike {
+    pub life: bool,
+    pub pos: Pos2D,
+    pub rule: LifeRule,
+}
+
+impl LifeLike {
+    pub fn new_life(pos: &Pos2D, rule: LifeRule) -> Self {
+        LifeLike {
+            life: true,
+            pos: *pos,
+            rule,
+        }
+    }
+
+    pub fn dead(pos: &Pos2D, rule: LifeRule) -> Self {
+        LifeLike {
+            life: false,
+            pos: *pos,
+            rule,
+        }
+    }
+
+    fn update_life(&self, neighborhood: &Neighborhood<Self>) -> bool {
+        let n = match self.rule.counting() {
+            Counting::Moore => neighborhood
+                .moore(&self.pos)
+                .values()
+                .filter(|c| c.life)
+                .count(),
+            Counting::Neumann => neighborhood
+                .neumann(&self.pos)
+                .values()
+                .filter(|c| c.life)
+                .count(),
+        };
+        self.rule.next(self.life, n)
+    }
+}
+
+impl AutomatonCell for LifeLike {
+    fn update(&self, neighborhood: &Neighborhood<Self>) -> Self {
+        LifeLike {
+            life: self.update_life(neighborhood),
+            ..*self
+        }
+    }
+
+    fn position(&self) -> Pos2D {
+        self.pos
+    }
+
+    fn new(pos: &Pos2D) -> Self {
+        LifeLike::dead(pos, LifeRule::CONWAY)
+    }
+
+    /// Rules with B0 bring dead cells surrounded by dead cells to life.
+    fn is_quiescent(&self) -> bool {
+        !self.life && !self.rule.born(0)
+    }
+}
+
+impl SnapshotCell for LifeLike {
+    /// The rule is saved as its string, e.g. "B36/S23".
+    fn save(&self, out: &mut Vec<u8>) {
+        out.push(self.life as u8);
+        snapshot::write_string(out, &self.rule.to_string());
+    }
+
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError> {
+        let life = snapshot::read_bool(input)?;
+        let rule = snapshot::read_string(input)?
+            .parse::<LifeRule>()
+            .map_err(|err| SnapshotError::Invalid(err.to_string()))?;
+        Ok(LifeLike {
+            life,
+            pos: *pos,
+            rule,
+        })
+    }
+}
+
+impl fmt::Debug for LifeLike {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LifeLike({:?} -> {:?}, {})",
+            self.pos, self.life, self.rule
+        )
+    }
+}
@@ -1,2 +1,8 @@
+pub mod forest_fire;
 pub mod game_of_life;
+pub mod generations;
+pub mod hex_life;
 pub mod langtons_ant;
+pub mod larger_than_life;
+pub mod life_like;
+pub mod sand;
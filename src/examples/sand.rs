@@ -0,0 +1,92 @@
+use crate::engine::automaton::*;
+use crate::engine::margolus::BlockCell;
+use crate::engine::snapshot::{self, SnapshotCell, SnapshotError};
+use crate::fields::Pos2D;
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Falling sand, to be run with `Margolus::new(Sand::fall)`. A grain falls down if there's
+/// nothing below it, or slides down to the side if it lies on another grain. On its own, through
+/// `AutomatonCell::update`, nothing moves.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Sand {
+    pub grain: bool,
+    pub pos: Pos2D,
+}
+
+impl Sand {
+    pub fn new_grain(pos: &Pos2D) -> Self {
+        Sand {
+            grain: true,
+            pos: *pos,
+        }
+    }
+
+    pub fn fall(block: &[Sand; 4]) -> [Sand; 4] {
+        let mut b = *block;
+        if b[0].grain && !b[2].grain {
+            b.swap(0, 2);
+        }
+        if b[1].grain && !b[3].grain {
+            b.swap(1, 3);
+        }
+        if b[0].grain && b[2].grain && !b[3].grain {
+            b.swap(0, 3);
+        } else if b[1].grain && b[3].grain && !b[2].grain {
+            b.swap(1, 2);
+        }
+        b
+    }
+}
+
+impl AutomatonCell for Sand {
+    fn update(&self, _neighborhood: &Neighborhood<Self>) -> Self {
+        *self
+    }
+
+    fn position(&self) -> Pos2D {
+        self.pos
+    }
+
+    fn new(pos: &Pos2D) -> Self {
+        Sand {
+            grain: false,
+            pos: *pos,
+        }
+    }
+
+    fn is_quiescent(&self) -> bool {
+        true
+    }
+}
+
+impl BlockCell for Sand {
+    fn with_position(&self, pos: &Pos2D) -> Self {
+        Sand {
+            grain: self.grain,
+            pos: *pos,
+        }
+    }
+}
+
+impl SnapshotCell for Sand {
+    fn save(&self, out: &mut Vec<u8>) {
+        out.push(self.grain as u8);
+    }
+
+    fn load(pos: &Pos2D, input: &mut &[u8]) -> Result<Self, SnapshotError> {
+        Ok(Sand {
+            grain: snapshot::read_bool(input)?,
+            pos: *pos,
+        })
+    }
+}
+
+impl fmt::Debug for Sand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Sand({:?} -> {:?})", self.pos, self.grain)
+    }
+}
@@ -2,7 +2,11 @@ use std::cmp::{max, min};
 use std::fmt;
 use std::vec::Vec;
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub struct Pos2D {
     pub x: i64,
     pub y: i64,
@@ -15,8 +19,8 @@ impl Pos2D {
         Pos2D { x, y }
     }
 
-    pub fn from_dim(dim: usize) -> Vec<Pos2D> {
-        Pos2D::from_range(POS2D_ZERO, Pos2D::new(dim as i64, dim as i64))
+    pub fn from_dims(width: usize, height: usize) -> Vec<Pos2D> {
+        Pos2D::from_range(POS2D_ZERO, Pos2D::new(width as i64, height as i64))
     }
 
     pub fn from_range(p1: Pos2D, p2: Pos2D) -> Vec<Pos2D> {
@@ -56,6 +60,17 @@ impl Pos2D {
     pub fn dir_to8(&self, pos: Pos2D) -> Dir2D {
         Dir2D::approx8(pos.x as f64 - self.x as f64, pos.y as f64 - self.y as f64)
     }
+
+    /// Treats `self` as the offset coordinates of a hex (see `Hex`) and moves it to the neighbouring hex.
+    pub fn move_by_one_hex(&self, dir: Dir6) -> Pos2D {
+        Hex::from_offset(self).move_by_one(dir).to_offset()
+    }
+
+    pub fn dir_to6(&self, pos: Pos2D) -> Dir6 {
+        let (x1, y1) = Hex::from_offset(self).to_pixel();
+        let (x2, y2) = Hex::from_offset(&pos).to_pixel();
+        Dir6::approx(x2 - x1, y2 - y1)
+    }
 }
 
 impl fmt::Display for Pos2D {
@@ -71,6 +86,7 @@ impl fmt::Debug for Pos2D {
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
 pub enum Dir2D {
     Up,
     Right,
@@ -98,6 +114,21 @@ lazy_static! {
 }
 
 impl Dir2D {
+    /// The position of the direction in `DIRS8` (and of the orthogonal ones, also in `DIRS4`).
+    #[inline]
+    pub fn index(self) -> usize {
+        match self {
+            Dir2D::Up => 0,
+            Dir2D::Right => 1,
+            Dir2D::Down => 2,
+            Dir2D::Left => 3,
+            Dir2D::UpLeft => 4,
+            Dir2D::UpRight => 5,
+            Dir2D::DownLeft => 6,
+            Dir2D::DownRight => 7,
+        }
+    }
+
     pub fn turn_right(self) -> Dir2D {
         match self {
             Dir2D::Up => Dir2D::Right,
@@ -222,6 +253,311 @@ impl fmt::Debug for Dir2D {
     }
 }
 
+/// One of the six directions on a grid of pointy-topped hexes, laid out in rows.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Dir6 {
+    Right,
+    UpRight,
+    UpLeft,
+    Left,
+    DownLeft,
+    DownRight,
+}
+
+lazy_static! {
+    pub static ref DIRS6: [Dir6; 6] = [
+        Dir6::Right,
+        Dir6::UpRight,
+        Dir6::UpLeft,
+        Dir6::Left,
+        Dir6::DownLeft,
+        Dir6::DownRight
+    ];
+}
+
+impl Dir6 {
+    /// The position of the direction in `DIRS6`.
+    #[inline]
+    pub fn index(self) -> usize {
+        match self {
+            Dir6::Right => 0,
+            Dir6::UpRight => 1,
+            Dir6::UpLeft => 2,
+            Dir6::Left => 3,
+            Dir6::DownLeft => 4,
+            Dir6::DownRight => 5,
+        }
+    }
+
+    pub fn turn_right(self) -> Dir6 {
+        match self {
+            Dir6::Right => Dir6::DownRight,
+            Dir6::DownRight => Dir6::DownLeft,
+            Dir6::DownLeft => Dir6::Left,
+            Dir6::Left => Dir6::UpLeft,
+            Dir6::UpLeft => Dir6::UpRight,
+            Dir6::UpRight => Dir6::Right,
+        }
+    }
+
+    pub fn turn_left(self) -> Dir6 {
+        match self {
+            Dir6::Right => Dir6::UpRight,
+            Dir6::UpRight => Dir6::UpLeft,
+            Dir6::UpLeft => Dir6::Left,
+            Dir6::Left => Dir6::DownLeft,
+            Dir6::DownLeft => Dir6::DownRight,
+            Dir6::DownRight => Dir6::Right,
+        }
+    }
+
+    pub fn turn_around(self) -> Dir6 {
+        match self {
+            Dir6::Right => Dir6::Left,
+            Dir6::UpRight => Dir6::DownLeft,
+            Dir6::UpLeft => Dir6::DownRight,
+            Dir6::Left => Dir6::Right,
+            Dir6::DownLeft => Dir6::UpRight,
+            Dir6::DownRight => Dir6::UpLeft,
+        }
+    }
+
+    /// The direction closest to the vector `(x, y)`. As everywhere else, `y` grows downwards.
+    pub fn approx(x: f64, y: f64) -> Dir6 {
+        let angle = (-y).atan2(x).to_degrees();
+        match ((angle / 60.0).round() as i64).rem_euclid(6) {
+            0 => Dir6::Right,
+            1 => Dir6::UpRight,
+            2 => Dir6::UpLeft,
+            3 => Dir6::Left,
+            4 => Dir6::DownLeft,
+            _ => Dir6::DownRight,
+        }
+    }
+}
+
+impl fmt::Display for Dir6 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dir = match *self {
+            Dir6::Right => "Right",
+            Dir6::UpRight => "UpRight",
+            Dir6::UpLeft => "UpLeft",
+            Dir6::Left => "Left",
+            Dir6::DownLeft => "DownLeft",
+            Dir6::DownRight => "DownRight",
+        };
+        write!(f, "Dir6({})", dir)
+    }
+}
+
+impl fmt::Debug for Dir6 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dir = match *self {
+            Dir6::Right => "Right",
+            Dir6::UpRight => "UpRight",
+            Dir6::UpLeft => "UpLeft",
+            Dir6::Left => "Left",
+            Dir6::DownLeft => "DownLeft",
+            Dir6::DownRight => "DownRight",
+        };
+        write!(f, "Dir6({:?})", dir)
+    }
+}
+
+/// Axial coordinates of a pointy-topped hex.
+///
+/// Boards are rectangular, so on the board hexes are stored in "odd-r" offset coordinates: rows
+/// are kept as they are and every odd row is shifted half a hex to the right. `Hex` is used to
+/// do the maths (neighbours, distances, directions) and `from_offset`/`to_offset` convert between
+/// the two. The third, cube coordinate is `s = -q - r`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub fn new(q: i64, r: i64) -> Hex {
+        Hex { q, r }
+    }
+
+    pub fn from_cube(x: i64, y: i64, z: i64) -> Hex {
+        debug_assert!(x + y + z == 0);
+        Hex { q: x, r: z }
+    }
+
+    /// Rounds fractional axial coordinates to the hex they fall into.
+    pub fn from_fractional(q: f64, r: f64) -> Hex {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Hex::new(rq as i64, rr as i64)
+    }
+
+    pub fn from_offset(pos: &Pos2D) -> Hex {
+        Hex::new(pos.x - (pos.y - (pos.y & 1)) / 2, pos.y)
+    }
+
+    pub fn to_offset(&self) -> Pos2D {
+        Pos2D::new(self.q + (self.r - (self.r & 1)) / 2, self.r)
+    }
+
+    #[inline]
+    pub fn s(&self) -> i64 {
+        -self.q - self.r
+    }
+
+    #[inline]
+    pub fn cube(&self) -> (i64, i64, i64) {
+        (self.q, -self.q - self.r, self.r)
+    }
+
+    pub fn move_by_one(&self, dir: Dir6) -> Hex {
+        match dir {
+            Dir6::Right => Hex::new(self.q + 1, self.r),
+            Dir6::UpRight => Hex::new(self.q + 1, self.r - 1),
+            Dir6::UpLeft => Hex::new(self.q, self.r - 1),
+            Dir6::Left => Hex::new(self.q - 1, self.r),
+            Dir6::DownLeft => Hex::new(self.q - 1, self.r + 1),
+            Dir6::DownRight => Hex::new(self.q, self.r + 1),
+        }
+    }
+
+    pub fn distance(&self, other: &Hex) -> i64 {
+        ((self.q - other.q).abs() + (self.r - other.r).abs() + (self.s() - other.s()).abs()) / 2
+    }
+
+    /// The center of the hex, assuming hexes of size (center to corner) 1.0 and the center of
+    /// `Hex(0, 0)` at `(0.0, 0.0)`.
+    pub fn to_pixel(&self) -> (f64, f64) {
+        let sqrt3 = 3.0f64.sqrt();
+        (
+            sqrt3 * (self.q as f64 + self.r as f64 / 2.0),
+            1.5 * self.r as f64,
+        )
+    }
+
+    /// The inverse of `to_pixel`: the hex the point `(x, y)` falls into.
+    pub fn from_pixel(x: f64, y: f64) -> Hex {
+        let sqrt3 = 3.0f64.sqrt();
+        Hex::from_fractional(sqrt3 / 3.0 * x - y / 3.0, 2.0 / 3.0 * y)
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hex({}, {})", self.q, self.r)
+    }
+}
+
+impl fmt::Debug for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hex({}, {})", self.q, self.r)
+    }
+}
+
+/// A discrete position in a voxel world. The 3D counterpart of `Pos2D`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Pos3D {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+const POS3D_ZERO: Pos3D = Pos3D { x: 0, y: 0, z: 0 };
+
+lazy_static! {
+    /// Offsets to the six face neighbours of a voxel.
+    pub static ref NEUMANN3D: [Pos3D; 6] = [
+        Pos3D::new(0, -1, 0),
+        Pos3D::new(1, 0, 0),
+        Pos3D::new(0, 1, 0),
+        Pos3D::new(-1, 0, 0),
+        Pos3D::new(0, 0, -1),
+        Pos3D::new(0, 0, 1)
+    ];
+    /// Offsets to all 26 voxels touching a voxel by a face, an edge or a corner, ordered by `z`, `y`, `x`.
+    pub static ref MOORE3D: [Pos3D; 26] = {
+        let mut offsets = [POS3D_ZERO; 26];
+        let mut i = 0;
+        for z in -1..=1 {
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    if x != 0 || y != 0 || z != 0 {
+                        offsets[i] = Pos3D::new(x, y, z);
+                        i += 1;
+                    }
+                }
+            }
+        }
+        offsets
+    };
+}
+
+impl Pos3D {
+    pub fn new(x: i64, y: i64, z: i64) -> Pos3D {
+        Pos3D { x, y, z }
+    }
+
+    pub fn from_dims(width: usize, height: usize, depth: usize) -> Vec<Pos3D> {
+        Pos3D::from_range(
+            POS3D_ZERO,
+            Pos3D::new(width as i64, height as i64, depth as i64),
+        )
+    }
+
+    pub fn from_range(p1: Pos3D, p2: Pos3D) -> Vec<Pos3D> {
+        let xfrom = min(p1.x, p2.x);
+        let yfrom = min(p1.y, p2.y);
+        let zfrom = min(p1.z, p2.z);
+        let xto = max(p1.x, p2.x);
+        let yto = max(p1.y, p2.y);
+        let zto = max(p1.z, p2.z);
+
+        let mut v = Vec::with_capacity(((xto - xfrom) * (yto - yfrom) * (zto - zfrom)) as usize);
+        for z in zfrom..zto {
+            for y in yfrom..yto {
+                for x in xfrom..xto {
+                    v.push(Pos3D::new(x, y, z))
+                }
+            }
+        }
+
+        v
+    }
+}
+
+impl Add<Pos3D> for Pos3D {
+    type Output = Pos3D;
+
+    fn add(self, other: Pos3D) -> Pos3D {
+        Pos3D::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl From<Pos2D> for Pos3D {
+    fn from(pos: Pos2D) -> Self {
+        Pos3D::new(pos.x, pos.y, 0)
+    }
+}
+
+impl fmt::Display for Pos3D {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl fmt::Debug for Pos3D {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum WhiteBlack {
     White = 0,
@@ -625,6 +961,13 @@ impl RGB {
     ];
 }
 
+/// Written as in HTML, e.g. `#ffa500`.
+impl fmt::Display for RGB {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
 impl Add<RGB> for RGB {
     type Output = RGB;
 
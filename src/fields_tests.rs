@@ -87,4 +87,77 @@ mod fields_tests {
         assert_that(&v2[2]).is_equal_to(Coeff::new(0.4));
         assert_that(&v2[3]).is_equal_to(Coeff::new(0.2));
     }
+
+    #[test]
+    fn should_index_dir2d_as_in_dirs8() {
+        for (i, &dir) in DIRS8.iter().enumerate() {
+            assert_that!(dir.index()).is_equal_to(i);
+        }
+        for (i, &dir) in DIRS4.iter().enumerate() {
+            assert_that!(dir.index()).is_equal_to(i);
+        }
+    }
+
+    #[test]
+    fn should_turn_dir6() {
+        for &dir in DIRS6.iter() {
+            assert_that!(dir.turn_right().turn_left()).is_equal_to(dir);
+            assert_that!(dir.turn_around().turn_around()).is_equal_to(dir);
+            assert_that!(dir.turn_right().turn_right().turn_right()).is_equal_to(dir.turn_around());
+        }
+        assert_that!(Dir6::Right.turn_left()).is_equal_to(Dir6::UpRight);
+        assert_that!(Dir6::Right.turn_right()).is_equal_to(Dir6::DownRight);
+    }
+
+    #[test]
+    fn should_approximate_dir6() {
+        assert_that!(Dir6::approx(1.0, 0.1)).is_equal_to(Dir6::Right);
+        assert_that!(Dir6::approx(0.5, -0.9)).is_equal_to(Dir6::UpRight);
+        assert_that!(Dir6::approx(-0.5, -0.9)).is_equal_to(Dir6::UpLeft);
+        assert_that!(Dir6::approx(-1.0, 0.0)).is_equal_to(Dir6::Left);
+        assert_that!(Dir6::approx(-0.5, 0.9)).is_equal_to(Dir6::DownLeft);
+        assert_that!(Dir6::approx(0.5, 0.9)).is_equal_to(Dir6::DownRight);
+    }
+
+    #[test]
+    fn should_convert_hex_coordinates() {
+        for y in -3..4 {
+            for x in -3..4 {
+                let pos = Pos2D::new(x, y);
+                assert_that!(Hex::from_offset(&pos).to_offset()).is_equal_to(pos);
+            }
+        }
+        let hex = Hex::new(2, -5);
+        assert_that!(hex.cube()).is_equal_to((2, 3, -5));
+        assert_that!(Hex::from_cube(2, 3, -5)).is_equal_to(hex);
+    }
+
+    #[test]
+    fn should_move_hex() {
+        let hex = Hex::new(1, 1);
+        for &dir in DIRS6.iter() {
+            let moved = hex.move_by_one(dir);
+            assert_that!(hex.distance(&moved)).is_equal_to(1);
+            assert_that!(moved.move_by_one(dir.turn_around())).is_equal_to(hex);
+            let (x1, y1) = hex.to_pixel();
+            let (x2, y2) = moved.to_pixel();
+            assert_that!(Dir6::approx(x2 - x1, y2 - y1)).is_equal_to(dir);
+            assert_that!(Hex::from_pixel(x2, y2)).is_equal_to(moved);
+        }
+        assert_that!(hex.distance(&Hex::new(-2, 3))).is_equal_to(3);
+    }
+
+    #[test]
+    fn should_find_hex_neighbours_in_offset_rows() {
+        // even rows are not shifted, odd rows are shifted half a hex to the right
+        let even = Pos2D::new(2, 2);
+        assert_that!(even.move_by_one_hex(Dir6::UpLeft)).is_equal_to(Pos2D::new(1, 1));
+        assert_that!(even.move_by_one_hex(Dir6::UpRight)).is_equal_to(Pos2D::new(2, 1));
+        assert_that!(even.move_by_one_hex(Dir6::DownRight)).is_equal_to(Pos2D::new(2, 3));
+        let odd = Pos2D::new(2, 1);
+        assert_that!(odd.move_by_one_hex(Dir6::UpLeft)).is_equal_to(Pos2D::new(2, 0));
+        assert_that!(odd.move_by_one_hex(Dir6::UpRight)).is_equal_to(Pos2D::new(3, 0));
+        assert_that!(odd.move_by_one_hex(Dir6::DownLeft)).is_equal_to(Pos2D::new(2, 2));
+        assert_that!(odd.dir_to6(Pos2D::new(3, 2))).is_equal_to(Dir6::DownRight);
+    }
 }
@@ -12,6 +12,7 @@ pub mod engine;
 pub mod examples;
 pub mod fields;
 pub mod ids;
+pub mod patterns;
 pub mod utils;
 pub mod visualisation;
 
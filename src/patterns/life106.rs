@@ -0,0 +1,59 @@
+//! The Life 1.06 format: a header and the coordinates of the live cells, one per line, which may
+//! be negative. E.g. a glider around `(0, 0)`:
+//!
+//! ```text
+//! #Life 1.06
+//! 0 -1
+//! 1 0
+//! -1 1
+//! 0 1
+//! 1 1
+//! ```
+use crate::fields::Pos2D;
+use crate::patterns::pattern::{Pattern, PatternError};
+
+pub const HEADER: &str = "#Life 1.06";
+
+/// Reads a pattern. It's as big as it has to be to hold all the cells, and its offset is
+/// the position of its top left corner in the file's coordinates, so that it can be put on a board
+/// with `(0, 0)` at any point with `Pattern::place_around`. Other lines starting with `#` are
+/// skipped. Fails with `PatternError::TooLarge` if the cells are too far apart.
+pub fn read(s: &str) -> Result<Pattern, PatternError> {
+    let mut lines = s
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    match lines.next() {
+        Some((_, line)) if line.trim() == HEADER => {}
+        Some((_, line)) => return Err(PatternError::InvalidHeader(line.to_string())),
+        None => return Err(PatternError::InvalidHeader(String::new())),
+    }
+
+    let mut cells = Vec::new();
+    for (number, line) in lines {
+        if line.starts_with('#') {
+            continue;
+        }
+        let coordinates: Vec<&str> = line.split_whitespace().collect();
+        match coordinates.as_slice() {
+            [x, y] => match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => cells.push(Pos2D::new(x, y)),
+                _ => return Err(PatternError::InvalidLine(number + 1)),
+            },
+            _ => return Err(PatternError::InvalidLine(number + 1)),
+        }
+    }
+    Pattern::from_cells(&cells)
+}
+
+/// Writes the cells in the pattern's own coordinates (see `Pattern::offset`), row by row.
+/// The format has no place for the name, the comments, the rule or the size, so reading it back
+/// gives the smallest pattern holding all the cells.
+pub fn write(pattern: &Pattern) -> String {
+    let mut s = format!("{}\n", HEADER);
+    pattern
+        .cells_around_origin()
+        .iter()
+        .for_each(|pos| s.push_str(&format!("{} {}\n", pos.x, pos.y)));
+    s
+}
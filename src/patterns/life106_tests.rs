@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod life106_tests {
+    use crate::engine::automaton::*;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::fields::Pos2D;
+    use crate::patterns::pattern::*;
+    use crate::patterns::{life106, plaintext};
+
+    use spectral::prelude::*;
+
+    const GLIDER: &str = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+
+    #[test]
+    fn should_read_negative_coordinates() {
+        let glider = life106::read(GLIDER).unwrap();
+        assert_that!(glider.width()).is_equal_to(3);
+        assert_that!(glider.height()).is_equal_to(3);
+        assert_that!(glider.offset).is_equal_to(Pos2D::new(-1, -1));
+        let plain = plaintext::read(".O.\n..O\nOOO").unwrap();
+        assert_that!(glider.cells()).is_equal_to(plain.cells());
+    }
+
+    #[test]
+    fn should_place_pattern_around_origin() {
+        let glider = life106::read(GLIDER).unwrap();
+        let board = glider.place_around(
+            &Board::<GameOfLife>::new(8, 8),
+            &Pos2D::new(4, 4),
+            GameOfLife::new_life,
+        );
+        let alive: Vec<Pos2D> = board
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect();
+        assert_that!(alive).is_equal_to(vec![
+            Pos2D::new(4, 3),
+            Pos2D::new(5, 4),
+            Pos2D::new(3, 5),
+            Pos2D::new(4, 5),
+            Pos2D::new(5, 5),
+        ]);
+    }
+
+    #[test]
+    fn should_skip_comments_and_empty_lines() {
+        let pattern = life106::read("\n#Life 1.06\n#D a block\n0 0\n\n1  0\n0 1\n1 1\n").unwrap();
+        assert_that!(pattern.len()).is_equal_to(4);
+        assert_that!(pattern.offset).is_equal_to(Pos2D::new(0, 0));
+    }
+
+    #[test]
+    fn should_not_read_invalid_files() {
+        assert_that!(life106::read(""))
+            .is_equal_to(Err(PatternError::InvalidHeader(String::new())));
+        assert_that!(life106::read("#Life 1.05\n0 0"))
+            .is_equal_to(Err(PatternError::InvalidHeader("#Life 1.05".to_string())));
+        assert_that!(life106::read("#Life 1.06\n0 0\n1\n"))
+            .is_equal_to(Err(PatternError::InvalidLine(3)));
+        assert_that!(life106::read("#Life 1.06\n0 x\n"))
+            .is_equal_to(Err(PatternError::InvalidLine(2)));
+    }
+
+    #[test]
+    fn should_not_read_cells_too_far_apart() {
+        let far = format!("#Life 1.06\n{} 0\n{} 0\n", i64::MIN, i64::MAX);
+        assert_that!(life106::read(&far)).is_equal_to(Err(PatternError::TooLarge));
+
+        // it fits, but it's too large to be written with every dead cell
+        let wide = life106::read("#Life 1.06\n-4000000000 0\n4000000000 5\n").unwrap();
+        assert_that!(wide.width()).is_equal_to(8_000_000_001);
+        assert_that!(plaintext::write(&wide)).is_equal_to(Err(PatternError::TooLarge));
+        assert_that!(life106::read(&life106::write(&wide))).is_equal_to(Ok(wide));
+    }
+
+    #[test]
+    fn should_write_pattern_as_read() {
+        let glider = life106::read(GLIDER).unwrap();
+        let s = life106::write(&glider);
+        assert_that!(s.clone()).is_equal_to(GLIDER.to_string());
+        assert_that!(life106::read(&s)).is_equal_to(Ok(glider));
+
+        // without an offset the pattern starts at (0, 0)
+        let plain = plaintext::read("..\n.O").unwrap();
+        assert_that!(life106::write(&plain)).is_equal_to("#Life 1.06\n1 1\n".to_string());
+        assert_that!(life106::write(&Pattern::default())).is_equal_to("#Life 1.06\n".to_string());
+    }
+}
@@ -0,0 +1,13 @@
+pub mod life106;
+pub mod pattern;
+pub mod plaintext;
+pub mod rle;
+
+#[cfg(test)]
+mod life106_tests;
+#[cfg(test)]
+mod pattern_tests;
+#[cfg(test)]
+mod plaintext_tests;
+#[cfg(test)]
+mod rle_tests;
@@ -0,0 +1,198 @@
+use crate::engine::automaton::{AutomatonCell, Board};
+use crate::fields::Pos2D;
+
+use std::error::Error;
+use std::fmt;
+
+/// The largest number of cells, dead or alive, of a pattern which is read or written in a format
+/// which can make the reader allocate memory for every cell, e.g. RLE with its header and runs.
+pub const MAX_CELLS: usize = 1 << 24;
+
+/// A two-state pattern, e.g. read from a file: the positions of its live cells, relative to
+/// the top left corner of a `width` x `height` rectangle, sorted row by row.
+/// The formats are in their own modules, e.g. `rle::read` and `rle::write`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<Pos2D>,
+    /// Where the top left corner of the pattern is in its own coordinates, e.g. `(-1, -1)` for
+    /// a Life 1.06 pattern around `(0, 0)`. It's `(0, 0)` for formats which start at the corner.
+    pub offset: Pos2D,
+    pub name: Option<String>,
+    pub comments: Vec<String>,
+    /// The rule the pattern is meant for, as written in the file, e.g. "B3/S23"
+    /// (see `LifeRule` for parsing it).
+    pub rule: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The header of the file (`x = 3, y = 3, rule = B3/S23` in RLE, `#Life 1.06` in Life 1.06)
+    /// is missing or can't be read.
+    InvalidHeader(String),
+    InvalidCharacter {
+        line: usize,
+        character: char,
+    },
+    /// A live cell outside of the size of the pattern.
+    OutOfBounds(Pos2D),
+    /// A line which should hold the coordinates of a cell, but doesn't.
+    InvalidLine(usize),
+    /// The cells are too far apart for the size of the pattern to be computed, or the pattern
+    /// has more than `MAX_CELLS` cells in a format which doesn't allow it.
+    TooLarge,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::InvalidHeader(header) => {
+                write!(f, "the header is missing or can't be read: \"{}\"", header)
+            }
+            PatternError::InvalidCharacter { line, character } => {
+                write!(f, "unexpected character '{}' in line {}", character, line)
+            }
+            PatternError::OutOfBounds(pos) => {
+                write!(f, "the cell at {} is outside of the pattern", pos)
+            }
+            PatternError::InvalidLine(line) => {
+                write!(f, "expected the coordinates of a cell in line {}", line)
+            }
+            PatternError::TooLarge => write!(f, "the pattern is too large"),
+        }
+    }
+}
+
+impl Error for PatternError {}
+
+impl Pattern {
+    /// Fails if any of the cells is outside of the `width` x `height` rectangle.
+    pub fn new(width: usize, height: usize, cells: &[Pos2D]) -> Result<Self, PatternError> {
+        if let Some(pos) = cells.iter().find(|pos| {
+            pos.x < 0 || pos.y < 0 || pos.x as usize >= width || pos.y as usize >= height
+        }) {
+            return Err(PatternError::OutOfBounds(*pos));
+        }
+        let mut cells = cells.to_vec();
+        cells.sort_by_key(|pos| (pos.y, pos.x));
+        cells.dedup();
+        Ok(Pattern {
+            width,
+            height,
+            cells,
+            ..Pattern::default()
+        })
+    }
+
+    /// The smallest pattern with the given cells, moved so that it starts at `(0, 0)`, with
+    /// the offset where it was before. Fails if its width or height don't fit in an `i64`.
+    pub fn from_cells(cells: &[Pos2D]) -> Result<Self, PatternError> {
+        if cells.is_empty() {
+            return Ok(Pattern::default());
+        }
+        let min_x = cells.iter().map(|pos| pos.x).min().unwrap();
+        let min_y = cells.iter().map(|pos| pos.y).min().unwrap();
+        let max_x = cells.iter().map(|pos| pos.x).max().unwrap();
+        let max_y = cells.iter().map(|pos| pos.y).max().unwrap();
+        let extent = |min: i64, max: i64| {
+            max.checked_sub(min)
+                .and_then(|d| d.checked_add(1))
+                .ok_or(PatternError::TooLarge)
+        };
+        let (width, height) = (extent(min_x, max_x)?, extent(min_y, max_y)?);
+        let moved: Vec<Pos2D> = cells
+            .iter()
+            .map(|pos| Pos2D::new(pos.x - min_x, pos.y - min_y))
+            .collect();
+        let mut pattern = Pattern::new(width as usize, height as usize, &moved)?;
+        pattern.offset = Pos2D::new(min_x, min_y);
+        Ok(pattern)
+    }
+
+    /// The live cells from the `width` x `height` rectangle of `board` starting at `from`.
+    /// Positions outside of the board (behind a wall) are dead.
+    pub fn from_board<C: AutomatonCell>(
+        board: &Board<C>,
+        from: &Pos2D,
+        width: usize,
+        height: usize,
+        is_alive: impl Fn(&C) -> bool,
+    ) -> Self {
+        let cells: Vec<Pos2D> = Pos2D::from_dims(width, height)
+            .into_iter()
+            .filter(|pos| {
+                board
+                    .get(&Pos2D::new(from.x + pos.x, from.y + pos.y))
+                    .is_some_and(&is_alive)
+            })
+            .collect();
+        Pattern::new(width, height, &cells).unwrap()
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn cells(&self) -> &[Pos2D] {
+        &self.cells
+    }
+
+    /// The number of live cells.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// A copy of `board` with the pattern's top left corner at `at`, and the live cells made by
+    /// `new_life`. The dead cells of the pattern don't change the board, and the live ones which
+    /// don't fit on it are left out, even on a torus.
+    pub fn place<C: AutomatonCell>(
+        &self,
+        board: &Board<C>,
+        at: &Pos2D,
+        new_life: impl Fn(&Pos2D) -> C,
+    ) -> Board<C> {
+        let (width, height) = (board.width() as i64, board.height() as i64);
+        let cells: Vec<C> = self
+            .cells
+            .iter()
+            .map(|pos| Pos2D::new(at.x + pos.x, at.y + pos.y))
+            .filter(|pos| pos.x >= 0 && pos.y >= 0 && pos.x < width && pos.y < height)
+            .map(|pos| new_life(&pos))
+            .collect();
+        board.copy_and_update(&cells)
+    }
+
+    /// Like `place`, but with the point `(0, 0)` of the pattern's own coordinates (see `offset`)
+    /// at `origin`.
+    pub fn place_around<C: AutomatonCell>(
+        &self,
+        board: &Board<C>,
+        origin: &Pos2D,
+        new_life: impl Fn(&Pos2D) -> C,
+    ) -> Board<C> {
+        let at = Pos2D::new(origin.x + self.offset.x, origin.y + self.offset.y);
+        self.place(board, &at, new_life)
+    }
+
+    /// The cells in the pattern's own coordinates (see `offset`).
+    pub fn cells_around_origin(&self) -> Vec<Pos2D> {
+        self.cells
+            .iter()
+            .map(|pos| Pos2D::new(pos.x + self.offset.x, pos.y + self.offset.y))
+            .collect()
+    }
+}
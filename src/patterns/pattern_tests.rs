@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod pattern_tests {
+    use crate::engine::automaton::*;
+    use crate::engine::boundary::Boundary;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::fields::Pos2D;
+    use crate::patterns::pattern::*;
+
+    use spectral::prelude::*;
+
+    fn positions(cells: &[(i64, i64)]) -> Vec<Pos2D> {
+        cells.iter().map(|&(x, y)| Pos2D::new(x, y)).collect()
+    }
+
+    #[test]
+    fn should_sort_cells() {
+        let pattern = Pattern::new(3, 2, &positions(&[(2, 1), (0, 1), (1, 0), (0, 1)])).unwrap();
+        assert_that!(pattern.cells().to_vec()).is_equal_to(positions(&[(1, 0), (0, 1), (2, 1)]));
+        assert_that!(pattern.len()).is_equal_to(3);
+
+        assert_that!(Pattern::new(3, 2, &positions(&[(3, 1)])))
+            .is_equal_to(Err(PatternError::OutOfBounds(Pos2D::new(3, 1))));
+        assert_that!(Pattern::new(3, 2, &positions(&[(0, -1)])))
+            .is_equal_to(Err(PatternError::OutOfBounds(Pos2D::new(0, -1))));
+    }
+
+    #[test]
+    fn should_fit_pattern_to_cells() {
+        let pattern = Pattern::from_cells(&positions(&[(5, -2), (7, -1), (6, 0)])).unwrap();
+        assert_that!(pattern.width()).is_equal_to(3);
+        assert_that!(pattern.height()).is_equal_to(3);
+        assert_that!(pattern.cells().to_vec()).is_equal_to(positions(&[(0, 0), (2, 1), (1, 2)]));
+        assert_that!(pattern.offset).is_equal_to(Pos2D::new(5, -2));
+        assert_that!(pattern.cells_around_origin()).is_equal_to(positions(&[
+            (5, -2),
+            (7, -1),
+            (6, 0),
+        ]));
+        assert_that!(Pattern::from_cells(&[]).unwrap().is_empty()).is_true();
+    }
+
+    #[test]
+    fn should_place_pattern_on_board() {
+        let pattern = Pattern::from_cells(&positions(&[(0, 0), (1, 0), (1, 1)])).unwrap();
+        let board = pattern.place(
+            &Board::<GameOfLife>::new(5, 5),
+            &Pos2D::new(3, 3),
+            GameOfLife::new_life,
+        );
+        let alive: Vec<Pos2D> = board
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect();
+        assert_that!(alive).is_equal_to(positions(&[(3, 3), (4, 3), (4, 4)]));
+
+        // the cells which don't fit are left out
+        let board = pattern.place(
+            &Board::<GameOfLife>::new(5, 5),
+            &Pos2D::new(4, 4),
+            GameOfLife::new_life,
+        );
+        let alive: Vec<Pos2D> = board
+            .iter()
+            .filter(|(_, c)| c.life)
+            .map(|(_, c)| c.pos)
+            .collect();
+        assert_that!(alive).is_equal_to(positions(&[(4, 4)]));
+    }
+
+    #[test]
+    fn should_read_pattern_from_board() {
+        let dead = GameOfLife::new(&Pos2D::new(-1, -1));
+        let board = Board::with_boundary(4, 4, Boundary::Wall(dead)).copy_and_update(&[
+            GameOfLife::new_life(&Pos2D::new(0, 0)),
+            GameOfLife::new_life(&Pos2D::new(2, 1)),
+            GameOfLife::new_life(&Pos2D::new(3, 3)),
+        ]);
+        let pattern = Pattern::from_board(&board, &Pos2D::new(-1, 0), 4, 3, |c| c.life);
+        assert_that!(pattern.width()).is_equal_to(4);
+        assert_that!(pattern.height()).is_equal_to(3);
+        assert_that!(pattern.cells().to_vec()).is_equal_to(positions(&[(1, 0), (3, 1)]));
+    }
+}
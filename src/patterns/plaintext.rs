@@ -0,0 +1,82 @@
+//! The plaintext format of LifeWiki (`.cells` files), e.g. a glider:
+//!
+//! ```text
+//! !Name: Glider
+//! .O.
+//! ..O
+//! OOO
+//! ```
+use crate::fields::Pos2D;
+use crate::patterns::pattern::{Pattern, PatternError, MAX_CELLS};
+
+/// Reads a pattern. The `!Name:` line gives the name of the pattern, and the other lines starting
+/// with `!` the comments. Live cells are `O` (or `*`), and dead ones `.`. The rows may be of
+/// different lengths, and the pattern is as wide as the longest of them.
+pub fn read(s: &str) -> Result<Pattern, PatternError> {
+    let (mut name, mut comments) = (None, Vec::new());
+    let mut rows = Vec::new();
+    for (number, line) in s.lines().enumerate() {
+        if let Some(rest) = line.strip_prefix("!Name:") {
+            name = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix('!') {
+            comments.push(rest.trim().to_string());
+        } else {
+            rows.push((number + 1, line.trim_end()));
+        }
+    }
+    while rows.last().is_some_and(|(_, row)| row.is_empty()) {
+        rows.pop();
+    }
+
+    let mut cells = Vec::new();
+    for (y, (number, row)) in rows.iter().enumerate() {
+        for (x, character) in row.chars().enumerate() {
+            match character {
+                'O' | '*' => cells.push(Pos2D::new(x as i64, y as i64)),
+                '.' => {}
+                c => {
+                    return Err(PatternError::InvalidCharacter {
+                        line: *number,
+                        character: c,
+                    })
+                }
+            }
+        }
+    }
+
+    let width = rows.iter().map(|(_, row)| row.chars().count()).max();
+    let mut pattern = Pattern::new(width.unwrap_or(0), rows.len(), &cells)?;
+    pattern.name = name;
+    pattern.comments = comments;
+    Ok(pattern)
+}
+
+/// Writes the pattern with its name and comments. All the rows are as wide as the pattern,
+/// so that it keeps its size when read again. That takes a character for every cell, so patterns
+/// with more than `MAX_CELLS` cells are rejected with `PatternError::TooLarge`.
+pub fn write(pattern: &Pattern) -> Result<String, PatternError> {
+    match pattern.width().checked_mul(pattern.height()) {
+        Some(cells) if cells <= MAX_CELLS => {}
+        _ => return Err(PatternError::TooLarge),
+    }
+
+    let mut s = String::new();
+    if let Some(name) = &pattern.name {
+        s.push_str(&format!("!Name: {}\n", name));
+    }
+    pattern
+        .comments
+        .iter()
+        .for_each(|comment| s.push_str(&format!("!{}\n", comment)));
+
+    let mut rows = vec![vec!['.'; pattern.width()]; pattern.height()];
+    pattern
+        .cells()
+        .iter()
+        .for_each(|pos| rows[pos.y as usize][pos.x as usize] = 'O');
+    for row in rows {
+        s.extend(row);
+        s.push('\n');
+    }
+    Ok(s)
+}
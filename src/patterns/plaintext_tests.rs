@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod plaintext_tests {
+    use crate::fields::Pos2D;
+    use crate::patterns::pattern::*;
+    use crate::patterns::{plaintext, rle};
+
+    use spectral::prelude::*;
+
+    const GLIDER: &str = "!Name: Glider\n!The smallest spaceship.\n.O.\n..O\nOOO\n";
+
+    #[test]
+    fn should_read_glider() {
+        let glider = plaintext::read(GLIDER).unwrap();
+        assert_that!(glider.width()).is_equal_to(3);
+        assert_that!(glider.height()).is_equal_to(3);
+        assert_that!(glider.name.clone()).is_equal_to(Some("Glider".to_string()));
+        assert_that!(glider.comments.clone())
+            .is_equal_to(vec!["The smallest spaceship.".to_string()]);
+        // the same pattern as in RLE
+        let rle = rle::read("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        assert_that!(glider.cells()).is_equal_to(rle.cells());
+    }
+
+    #[test]
+    fn should_read_rows_of_different_lengths() {
+        let pattern = plaintext::read("!\n.O\n\n*..O  \n\n").unwrap();
+        assert_that!(pattern.width()).is_equal_to(4);
+        assert_that!(pattern.height()).is_equal_to(3);
+        assert_that!(pattern.comments.clone()).is_equal_to(vec![String::new()]);
+        assert_that!(pattern.cells().to_vec()).is_equal_to(vec![
+            Pos2D::new(1, 0),
+            Pos2D::new(0, 2),
+            Pos2D::new(3, 2),
+        ]);
+    }
+
+    #[test]
+    fn should_not_read_invalid_characters() {
+        assert_that!(plaintext::read("!Name: x\n.O.\n.o.")).is_equal_to(Err(
+            PatternError::InvalidCharacter {
+                line: 3,
+                character: 'o',
+            },
+        ));
+    }
+
+    #[test]
+    fn should_write_pattern_as_read() {
+        assert_that!(plaintext::write(&plaintext::read(GLIDER).unwrap()).unwrap())
+            .is_equal_to(GLIDER.to_string());
+
+        // the empty rows and columns stay
+        let pattern = Pattern::new(4, 3, &[Pos2D::new(1, 1)]).unwrap();
+        let s = plaintext::write(&pattern).unwrap();
+        assert_that!(s.clone()).is_equal_to("....\n.O..\n....\n".to_string());
+        assert_that!(plaintext::read(&s)).is_equal_to(Ok(pattern));
+    }
+
+    #[test]
+    fn should_convert_rle_to_plaintext() {
+        let gun = rle::read(
+            "x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!",
+        )
+        .unwrap();
+        let copy = plaintext::read(&plaintext::write(&gun).unwrap()).unwrap();
+        assert_that!(copy.cells()).is_equal_to(gun.cells());
+        assert_that!(copy.width()).is_equal_to(36);
+        assert_that!(copy.height()).is_equal_to(9);
+    }
+}
@@ -0,0 +1,178 @@
+//! The run length encoded format of Golly and LifeWiki, e.g. a glider:
+//!
+//! ```text
+//! #N Glider
+//! x = 3, y = 3, rule = B3/S23
+//! bob$2bo$3o!
+//! ```
+use crate::fields::Pos2D;
+use crate::patterns::pattern::{Pattern, PatternError, MAX_CELLS};
+
+/// The longest lines `write` makes, as recommended for the format.
+pub const MAX_LINE_LENGTH: usize = 70;
+
+// The value of `part` if it's `key = value`.
+fn header_value<'a>(part: &'a str, key: &str) -> Option<&'a str> {
+    let mut key_value = part.splitn(2, '=');
+    if key_value.next()?.trim() == key {
+        key_value.next().map(str::trim)
+    } else {
+        None
+    }
+}
+
+fn read_header(header: &str) -> Result<(usize, usize, Option<String>), PatternError> {
+    let invalid = || PatternError::InvalidHeader(header.to_string());
+    // the rule is the last one and can have commas in it, e.g. "rule = R5,C0,M1,S34..58,B34..45,NM"
+    let mut parts = header.splitn(3, ',');
+    let mut size = |key| {
+        parts
+            .next()
+            .and_then(|part| header_value(part, key))
+            .and_then(|value| value.parse::<usize>().ok())
+            .ok_or_else(invalid)
+    };
+    let (width, height) = (size("x")?, size("y")?);
+    let rule = parts
+        .next()
+        .map(|part| header_value(part, "rule").ok_or_else(invalid))
+        .transpose()?;
+
+    match width.checked_mul(height) {
+        Some(cells) if cells <= MAX_CELLS => Ok((width, height, rule.map(str::to_string))),
+        _ => Err(PatternError::TooLarge),
+    }
+}
+
+/// Reads a two-state pattern. The `#N` line gives the name of the pattern, and the `#C` lines
+/// the comments. Other lines starting with `#` are skipped. Patterns with more than `MAX_CELLS`
+/// cells, dead or alive, are rejected with `PatternError::TooLarge`.
+pub fn read(s: &str) -> Result<Pattern, PatternError> {
+    let mut lines = s.lines().enumerate();
+    let (mut name, mut comments) = (None, Vec::new());
+    let (width, height, rule) = loop {
+        match lines.next() {
+            Some((_, line)) if line.trim().is_empty() => {}
+            Some((_, line)) if line.starts_with("#N") => name = Some(line[2..].trim().to_string()),
+            Some((_, line)) if line.starts_with("#C") || line.starts_with("#c") => {
+                comments.push(line[2..].trim().to_string())
+            }
+            Some((_, line)) if line.starts_with('#') => {}
+            Some((_, line)) => break read_header(line.trim())?,
+            None => return Err(PatternError::InvalidHeader(String::new())),
+        }
+    };
+
+    let mut cells = Vec::new();
+    let (mut x, mut y) = (0i64, 0i64);
+    let mut count: Option<i64> = None;
+    'lines: for (number, line) in lines {
+        for character in line.chars() {
+            if let Some(digit) = character.to_digit(10) {
+                let n = count.unwrap_or(0);
+                count = Some(n.saturating_mul(10).saturating_add(i64::from(digit)));
+                continue;
+            }
+            let n = count.take().unwrap_or(1);
+            match character {
+                'b' | '.' => x = x.saturating_add(n),
+                'o' => {
+                    let end = x.saturating_add(n);
+                    if end > width as i64 || y >= height as i64 {
+                        return Err(PatternError::OutOfBounds(Pos2D::new(end - 1, y)));
+                    }
+                    if n > (MAX_CELLS - cells.len()) as i64 {
+                        return Err(PatternError::TooLarge);
+                    }
+                    cells.extend((x..end).map(|x| Pos2D::new(x, y)));
+                    x = end;
+                }
+                '$' => {
+                    y = y.saturating_add(n);
+                    x = 0;
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => {}
+                c => {
+                    return Err(PatternError::InvalidCharacter {
+                        line: number + 1,
+                        character: c,
+                    })
+                }
+            }
+        }
+    }
+
+    let mut pattern = Pattern::new(width, height, &cells)?;
+    pattern.name = name;
+    pattern.comments = comments;
+    pattern.rule = rule;
+    Ok(pattern)
+}
+
+fn push_run(body: &mut String, line_length: &mut usize, n: usize, tag: char) {
+    let run = if n == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", n, tag)
+    };
+    if *line_length + run.len() > MAX_LINE_LENGTH {
+        body.push('\n');
+        *line_length = 0;
+    }
+    body.push_str(&run);
+    *line_length += run.len();
+}
+
+/// Writes the pattern with its name, comments and rule, like Golly: dead cells at the ends of
+/// rows and empty rows at the bottom are left out.
+pub fn write(pattern: &Pattern) -> String {
+    let mut s = String::new();
+    if let Some(name) = &pattern.name {
+        s.push_str(&format!("#N {}\n", name));
+    }
+    pattern
+        .comments
+        .iter()
+        .for_each(|comment| s.push_str(&format!("#C {}\n", comment)));
+    s.push_str(&format!(
+        "x = {}, y = {}",
+        pattern.width(),
+        pattern.height()
+    ));
+    if let Some(rule) = &pattern.rule {
+        s.push_str(&format!(", rule = {}", rule));
+    }
+    s.push('\n');
+
+    let mut body = String::new();
+    let mut line_length = 0;
+    let (mut x, mut y) = (0, 0);
+    let cells = pattern.cells();
+    let mut i = 0;
+    while i < cells.len() {
+        let pos = cells[i];
+        if pos.y > y {
+            push_run(&mut body, &mut line_length, (pos.y - y) as usize, '$');
+            y = pos.y;
+            x = 0;
+        }
+        if pos.x > x {
+            push_run(&mut body, &mut line_length, (pos.x - x) as usize, 'b');
+        }
+        // the cells are sorted, so a run of live cells is a run of consecutive positions
+        let run = cells[i..]
+            .iter()
+            .enumerate()
+            .take_while(|&(j, cell)| cell.y == pos.y && cell.x == pos.x + j as i64)
+            .count();
+        push_run(&mut body, &mut line_length, run, 'o');
+        x = pos.x + run as i64;
+        i += run;
+    }
+    push_run(&mut body, &mut line_length, 1, '!');
+
+    s.push_str(&body);
+    s.push('\n');
+    s
+}
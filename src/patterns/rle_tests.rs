@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod rle_tests {
+    use crate::engine::automaton::*;
+    use crate::examples::game_of_life::GameOfLife;
+    use crate::fields::Pos2D;
+    use crate::patterns::pattern::*;
+    use crate::patterns::rle;
+
+    use spectral::prelude::*;
+
+    const GLIDER: &str = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+
+    const GUN: &str = "#N Gosper glider gun
+#C This was the first gun discovered.
+#C As its name suggests, it was discovered by Bill Gosper.
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!
+";
+
+    fn positions(cells: &[(i64, i64)]) -> Vec<Pos2D> {
+        cells.iter().map(|&(x, y)| Pos2D::new(x, y)).collect()
+    }
+
+    #[test]
+    fn should_read_glider() {
+        let glider = rle::read(GLIDER).unwrap();
+        assert_that!(glider.width()).is_equal_to(3);
+        assert_that!(glider.height()).is_equal_to(3);
+        assert_that!(glider.cells().to_vec()).is_equal_to(positions(&[
+            (1, 0),
+            (2, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+        ]));
+        assert_that!(glider.name.clone()).is_equal_to(Some("Glider".to_string()));
+        assert_that!(glider.rule.clone()).is_equal_to(Some("B3/S23".to_string()));
+        // with the dead cells at the end of the first row, as on LifeWiki
+        let lifewiki = rle::read("x = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+        assert_that!(lifewiki.cells()).is_equal_to(glider.cells());
+    }
+
+    #[test]
+    fn should_read_glider_gun() {
+        let gun = rle::read(GUN).unwrap();
+        assert_that!(gun.len()).is_equal_to(36);
+        assert_that!(gun.comments.len()).is_equal_to(2);
+        assert_that!(gun.cells()[0]).is_equal_to(Pos2D::new(24, 0));
+        assert_that!(gun.cells()[35]).is_equal_to(Pos2D::new(13, 8));
+    }
+
+    #[test]
+    fn should_read_loose_formatting() {
+        let pattern = rle::read("#O someone\n\nx=4,y=5\n2o\n2$\n3bo  !\nignored").unwrap();
+        assert_that!(pattern.rule).is_none();
+        assert_that!(pattern.cells().to_vec()).is_equal_to(positions(&[(0, 0), (1, 0), (3, 2)]));
+
+        let pattern = rle::read("x = 12, y = 1\n10b2o!").unwrap();
+        assert_that!(pattern.cells().to_vec()).is_equal_to(positions(&[(10, 0), (11, 0)]));
+    }
+
+    #[test]
+    fn should_not_read_invalid_patterns() {
+        assert_that!(rle::read("")).is_equal_to(Err(PatternError::InvalidHeader(String::new())));
+        assert_that!(rle::read("bob$2bo$3o!"))
+            .is_equal_to(Err(PatternError::InvalidHeader("bob$2bo$3o!".to_string())));
+        assert_that!(rle::read("x = 3\nbo!"))
+            .is_equal_to(Err(PatternError::InvalidHeader("x = 3".to_string())));
+        assert_that!(rle::read("x = 3, y = 3\nbob$\n2bo$3q!")).is_equal_to(Err(
+            PatternError::InvalidCharacter {
+                line: 3,
+                character: 'q',
+            },
+        ));
+        assert_that!(rle::read("x = 3, y = 3\n2b2o!"))
+            .is_equal_to(Err(PatternError::OutOfBounds(Pos2D::new(3, 0))));
+        assert_that!(rle::read("x = 3, y = 3\n3$o!"))
+            .is_equal_to(Err(PatternError::OutOfBounds(Pos2D::new(0, 3))));
+        assert_that!(rle::read("y = 3, x = 3\no!"))
+            .is_equal_to(Err(PatternError::InvalidHeader("y = 3, x = 3".to_string())));
+    }
+
+    #[test]
+    fn should_not_read_too_large_patterns() {
+        assert_that!(rle::read("x = 100000, y = 100000\n99999bo!"))
+            .is_equal_to(Err(PatternError::TooLarge));
+        assert_that!(rle::read("x = 18446744073709551615, y = 2\no!"))
+            .is_equal_to(Err(PatternError::TooLarge));
+        assert_that!(rle::read("x = 4096, y = 4096\n4096o!")).is_ok();
+    }
+
+    #[test]
+    fn should_keep_commas_in_the_rule() {
+        let pattern = rle::read("x = 3, y = 1, rule = R5,C0,M1,S34..58,B34..45,NM\n3o!").unwrap();
+        assert_that!(pattern.rule).is_equal_to(Some("R5,C0,M1,S34..58,B34..45,NM".to_string()));
+        assert_that!(pattern.len()).is_equal_to(3);
+    }
+
+    #[test]
+    fn should_write_patterns_as_read() {
+        assert_that!(rle::write(&rle::read(GLIDER).unwrap())).is_equal_to(GLIDER.to_string());
+        assert_that!(rle::write(&rle::read(GUN).unwrap())).is_equal_to(GUN.to_string());
+
+        let empty = Pattern::new(4, 2, &[]).unwrap();
+        assert_that!(rle::write(&empty)).is_equal_to("x = 4, y = 2\n!\n".to_string());
+    }
+
+    #[test]
+    fn should_keep_lines_short() {
+        let cells: Vec<Pos2D> = (0..200).step_by(2).map(|x| Pos2D::new(x, 0)).collect();
+        let pattern = Pattern::new(200, 1, &cells).unwrap();
+        let s = rle::write(&pattern);
+        assert_that!(s.lines().all(|line| line.len() <= rle::MAX_LINE_LENGTH)).is_true();
+        assert_that!(rle::read(&s)).is_equal_to(Ok(pattern));
+    }
+
+    #[test]
+    fn should_write_board_region_and_read_it_back() {
+        let gun = rle::read(GUN).unwrap();
+        let board = gun.place(
+            &Board::<GameOfLife>::new(60, 40),
+            &Pos2D::new(5, 10),
+            GameOfLife::new_life,
+        );
+        let board = (0..30).fold(board, |board, _| board.update());
+
+        let region = Pattern::from_board(&board, &Pos2D::new(0, 0), 60, 40, |c| c.life);
+        let copy = rle::read(&rle::write(&region)).unwrap();
+        assert_that!(&copy).is_equal_to(&region);
+
+        let empty = Board::<GameOfLife>::new(60, 40);
+        let replayed = copy.place(&empty, &Pos2D::new(0, 0), GameOfLife::new_life);
+        assert_that!(replayed).is_equal_to(board);
+    }
+
+    quickcheck! {
+        fn should_round_trip_random_patterns(alive: Vec<bool>) -> bool {
+            let width = 13;
+            let height = alive.len() / width + 1;
+            let cells: Vec<Pos2D> = alive
+                .iter()
+                .enumerate()
+                .filter(|&(_, &a)| a)
+                .map(|(i, _)| Pos2D::new((i % width) as i64, (i / width) as i64))
+                .collect();
+            let pattern = Pattern::new(width, height, &cells).unwrap();
+            rle::read(&rle::write(&pattern)) == Ok(pattern)
+        }
+    }
+}
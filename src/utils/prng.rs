@@ -88,3 +88,26 @@ impl SeedableRng<[u32; 4]> for PseudoNormalRng {
         }
     }
 }
+
+#[inline]
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Mixes `values` into a seed for `XorShiftRng`. The same values always give the same seed, and
+/// changing any of them gives an unrelated one, so e.g. a board's seed and a generation number
+/// are enough to replay the same random choices.
+pub fn mix_seed(values: &[u64]) -> [u32; 4] {
+    let h1 = values.iter().fold(0u64, |h, &v| splitmix64(h ^ v));
+    let h2 = splitmix64(h1);
+    // XorShiftRng can't be seeded with zeros only
+    [
+        h1 as u32,
+        (h1 >> 32) as u32,
+        h2 as u32,
+        (h2 >> 32) as u32 | 1,
+    ]
+}
@@ -86,4 +86,12 @@ mod prng_tests {
             min, max, avg, dev
         );
     }
+
+    #[test]
+    fn should_mix_seeds() {
+        assert_that!(mix_seed(&[1, 2])).is_equal_to(mix_seed(&[1, 2]));
+        assert_that!(mix_seed(&[1, 2])).is_not_equal_to(mix_seed(&[2, 1]));
+        assert_that!(mix_seed(&[1, 2])).is_not_equal_to(mix_seed(&[1, 3]));
+        assert_that!(mix_seed(&[0, 0]) != [0; 4]).is_true();
+    }
 }
@@ -5,6 +5,7 @@ use std::clone::Clone;
 use std::cmp::{max, min};
 use std::fmt;
 use std::ops::{Add, BitXor, Mul, Sub};
+use std::thread;
 
 // TODO: https://doc.rust-lang.org/src/alloc/vec_deque.rs.html#1909-1913
 // rewrite in a similar fashion
@@ -126,6 +127,42 @@ where
         }
     }
 
+    /// Removes all values, but keeps the capacity, so that the map can be filled again without reallocation.
+    pub fn clear(&mut self) {
+        if !self.is_empty() {
+            self.vec.iter_mut().for_each(|v| *v = None);
+            self.len = 0;
+        }
+    }
+
+    /// Puts `f(key)` under every key from `0` to `len`, computing chunks of `chunk_size` keys
+    /// in separate threads. Only grows the map if `len` is bigger than its capacity.
+    pub fn put_all_parallel<F>(&mut self, len: usize, chunk_size: usize, f: F)
+    where
+        T: Send,
+        F: Fn(usize) -> T + Sync,
+    {
+        if len > self.vec.len() {
+            self.vec.resize(len, None);
+        }
+        let f = &f;
+        thread::scope(|scope| {
+            self.vec[..len]
+                .chunks_mut(max(1, chunk_size))
+                .enumerate()
+                .for_each(|(i, chunk)| {
+                    scope.spawn(move || {
+                        let offset = i * max(1, chunk_size);
+                        chunk
+                            .iter_mut()
+                            .enumerate()
+                            .for_each(|(key, value)| *value = Some(f(offset + key)));
+                    });
+                })
+        });
+        self.len = self.vec.iter().filter(|v| v.is_some()).count();
+    }
+
     pub fn remove(&mut self, key: usize) -> Option<T> {
         if key < self.vec.len() && self.vec[key].is_some() {
             let res = self.get(key);
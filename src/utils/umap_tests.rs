@@ -150,4 +150,26 @@ mod umap_tests {
         assert_that!(iter3.next()).is_equal_to(None);
         assert_that!(iter3.next()).is_equal_to(None);
     }
+
+    #[test]
+    fn clear_keeps_capacity() {
+        let mut map: UMap<i32> = vec![(2, 2), (4, 4), (5, 5)].into();
+        map.clear();
+        assert_that!(map.is_empty()).is_true();
+        assert_that!(map.capacity()).is_equal_to(6);
+        assert_that!(map.get(4)).is_equal_to(None);
+        map.put(1, 1);
+        assert_that!(map.len()).is_equal_to(1);
+    }
+
+    #[test]
+    fn put_all_in_parallel() {
+        let mut map: UMap<usize> = vec![(1, 0), (12, 0)].into();
+        map.put_all_parallel(10, 3, |key| key * key);
+        assert_that!(map.len()).is_equal_to(11);
+        assert_that!(map.capacity()).is_equal_to(13);
+        assert_that!(map.get(9)).is_equal_to(Some(81));
+        assert_that!(map.get(12)).is_equal_to(Some(0));
+        assert_that!(map.get(10)).is_equal_to(None);
+    }
 }
@@ -94,6 +94,14 @@ impl USet {
         }
     }
 
+    /// Removes all elements, but keeps the capacity.
+    pub fn clear(&mut self) {
+        if !self.is_empty() {
+            self.vec.iter_mut().for_each(|v| *v = false);
+            self.len = 0;
+        }
+    }
+
     pub fn remove(&mut self, id: usize) {
         if id < self.vec.len() && self.vec[id] {
             self.vec[id] = false;
@@ -205,4 +205,13 @@ mod uset_tests {
         let s6 = uset![10];
         assert_that!((&s1 ^ &s6)).is_equal_to(uset![0, 3, 8]);
     }
+
+    #[test]
+    fn should_clear() {
+        let mut s = uset![1, 4, 6];
+        s.clear();
+        assert_that!(s.is_empty()).is_true();
+        assert_that!(s.capacity()).is_equal_to(7);
+        assert_that!(s.contains(4)).is_false();
+    }
 }
@@ -10,10 +10,21 @@ use ggez::event::Mod;
 
 use itertools::Itertools;
 
+use std::cmp::min;
+
 use crate::engine::automaton::Automaton;
 use crate::engine::automaton::AutomatonCell;
+use crate::engine::automaton::Board;
+use crate::engine::life_rule::{GenerationsRule, LifeRule, LtlRule};
+use crate::examples::forest_fire::{ForestFire, Tree};
 use crate::examples::game_of_life::GameOfLife;
+use crate::examples::generations::Generations;
+use crate::examples::hex_life::HexLife;
 use crate::examples::langtons_ant::LangtonsAnt;
+use crate::examples::larger_than_life::LargerThanLife;
+use crate::examples::life_like::LifeLike;
+use crate::examples::sand::Sand;
+use crate::fields::Hex;
 use crate::fields::Pos2D;
 use crate::fields::RGB;
 
@@ -23,9 +34,39 @@ pub struct CellRectangle {
     color: RGB,
 }
 
+impl CellRectangle {
+    #[inline]
+    pub fn position(&self) -> Pos2D {
+        self.position
+    }
+
+    /// The color of the cell, e.g. to count the colours on the board with `Statistics`.
+    #[inline]
+    pub fn color(&self) -> RGB {
+        self.color
+    }
+}
+
+/// How the cells are laid out on the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tiling {
+    Square,
+    /// Pointy-topped hexes in rows, with every odd row shifted half a hex to the right.
+    Hex,
+}
+
 pub trait CellVisualisation: AutomatonCell {
     fn new_cell(pos: &Pos2D) -> Self;
     fn cell_to_rectangle(&self) -> CellRectangle;
+
+    /// The cell put in place of this one when it's clicked.
+    fn clicked(&self) -> Self {
+        Self::new_cell(&self.position())
+    }
+
+    fn tiling() -> Tiling {
+        Tiling::Square
+    }
 }
 
 pub struct MainState<C: CellVisualisation> {
@@ -35,25 +76,65 @@ pub struct MainState<C: CellVisualisation> {
     pause: bool,
 
     auto: Automaton<C>,
+    // computes the next generation instead of `Automaton::next`, if set
+    step: Option<Box<Fn(&Board<C>) -> Board<C>>>,
 }
 
 impl<C: CellVisualisation> MainState<C> {
-    pub fn new(window_size: usize, dim: usize) -> GameResult<MainState<C>> {
+    pub fn new(
+        window_width: usize,
+        window_height: usize,
+        width: usize,
+        height: usize,
+    ) -> GameResult<MainState<C>> {
+        let cell_size = match C::tiling() {
+            Tiling::Square => min(window_width / width, window_height / height),
+            Tiling::Hex => {
+                // `cell_size` is the width of a hex; rows overlap by a quarter of the hex's height
+                let w = window_width as f64 / (width as f64 + 0.5);
+                let h = window_height as f64 * 3.0f64.sqrt() / (1.5 * height as f64 + 0.5);
+                w.min(h) as usize
+            }
+        };
         Ok(MainState {
             iteration: 0,
-            cell_size: window_size / dim,
+            cell_size,
             pause: false,
-            auto: Automaton::<C>::new(dim),
+            auto: Automaton::<C>::new(width, height),
+            step: None,
         })
     }
 
+    /// Computes the generations with `step`, e.g. `Margolus::update`, instead of the rules of
+    /// the cells (see `Automaton::next_with`).
+    pub fn set_step(&mut self, step: impl Fn(&Board<C>) -> Board<C> + 'static) {
+        self.step = Some(Box::new(step));
+    }
+
+    /// Visualises an already prepared automaton, e.g. one with cells made by `Board::from_fn`.
+    pub fn with_automaton(
+        window_width: usize,
+        window_height: usize,
+        auto: Automaton<C>,
+    ) -> GameResult<MainState<C>> {
+        let mut state = MainState::new(window_width, window_height, auto.width(), auto.height())?;
+        state.auto = auto;
+        Ok(state)
+    }
+
     pub fn add(&mut self, pos: &Pos2D) {
-        self.auto.add_change(&C::new_cell(pos));
+        if let Some(cell) = self.auto.board().get(pos) {
+            let cell = cell.clicked();
+            self.auto.add_change(&cell);
+        }
     }
 
     fn update(&mut self) {
         if self.iteration > 0 {
-            self.auto.next();
+            match &self.step {
+                Some(step) => self.auto.next_with(|board| step(board)),
+                None => self.auto.next(),
+            }
         }
         self.iteration += 1;
     }
@@ -68,11 +149,55 @@ impl<C: CellVisualisation> MainState<C> {
         res
     }
 
+    #[inline]
+    fn hex_size(&self) -> f64 {
+        self.cell_size as f64 / 3.0f64.sqrt()
+    }
+
     fn pixels2pos(&self, x: usize, y: usize) -> Pos2D {
-        Pos2D::new((x / self.cell_size) as i64, (y / self.cell_size) as i64)
+        match C::tiling() {
+            Tiling::Square => Pos2D::new((x / self.cell_size) as i64, (y / self.cell_size) as i64),
+            Tiling::Hex => {
+                let size = self.hex_size();
+                let hx = (x as f64 - self.cell_size as f64 / 2.0) / size;
+                let hy = (y as f64 - size) / size;
+                Hex::from_pixel(hx, hy).to_offset()
+            }
+        }
     }
 
     fn draw(&mut self, ctx: &mut Context, positions: Vec<Pos2D>) {
+        match C::tiling() {
+            Tiling::Square => self.draw_squares(ctx, positions),
+            Tiling::Hex => self.draw_hexes(ctx, positions),
+        }
+    }
+
+    fn draw_hexes(&mut self, ctx: &mut Context, positions: Vec<Pos2D>) {
+        let size = self.hex_size();
+        let mut builder = MeshBuilder::new();
+        positions.iter().for_each(|p| {
+            let (hx, hy) = Hex::from_offset(p).to_pixel();
+            let cx = hx * size + self.cell_size as f64 / 2.0;
+            let cy = hy * size + size;
+            let pts: Vec<Point2> = (0..6)
+                .map(|i| {
+                    let angle = (60.0 * i as f64 - 30.0).to_radians();
+                    Point2::new(
+                        (cx + size * angle.cos()) as f32,
+                        (cy + size * angle.sin()) as f32,
+                    )
+                })
+                .collect();
+            builder.polygon(DrawMode::Fill, &pts);
+        });
+
+        if let Ok(mesh) = builder.build(ctx) {
+            mesh.draw(ctx, Point2::new(0.0, 0.0), 0.0).unwrap();
+        };
+    }
+
+    fn draw_squares(&mut self, ctx: &mut Context, positions: Vec<Pos2D>) {
         let mut builder = MeshBuilder::new();
         positions.iter().for_each(|p| {
             let x1 = (p.x as usize * self.cell_size) as f32;
@@ -128,7 +253,7 @@ impl<C: CellVisualisation> event::EventHandler for MainState<C> {
         let pos = self.pixels2pos(x as usize, y as usize);
         println!("Button clicked at: {}", pos);
         self.pause = true;
-        self.auto.add_change(&C::new_cell(&pos));
+        self.add(&pos);
         self.auto.apply_changes();
         self.draw_cells(ctx);
 
@@ -142,11 +267,11 @@ impl<C: CellVisualisation> event::EventHandler for MainState<C> {
     }
 }
 
-pub fn setup(ctx: &mut Context, window_size: usize) {
+pub fn setup(ctx: &mut Context, window_width: usize, window_height: usize) {
     graphics::set_background_color(ctx, graphics::WHITE);
     graphics::set_mode(
         ctx,
-        WindowMode::default().dimensions(window_size as u32, window_size as u32),
+        WindowMode::default().dimensions(window_width as u32, window_height as u32),
     )
     .unwrap();
     graphics::set_screen_coordinates(
@@ -154,8 +279,8 @@ pub fn setup(ctx: &mut Context, window_size: usize) {
         Rect {
             x: 0.0,
             y: 0.0,
-            w: window_size as f32,
-            h: window_size as f32,
+            w: window_width as f32,
+            h: window_height as f32,
         },
     )
     .unwrap();
@@ -182,6 +307,105 @@ impl CellVisualisation for LangtonsAnt {
     }
 }
 
+impl CellVisualisation for HexLife {
+    fn new_cell(pos: &Pos2D) -> Self {
+        HexLife::new_life(pos)
+    }
+
+    fn cell_to_rectangle(&self) -> CellRectangle {
+        let position = self.pos;
+        let color = if self.life { RGB::BLACK } else { RGB::WHITE };
+        CellRectangle { position, color }
+    }
+
+    fn tiling() -> Tiling {
+        Tiling::Hex
+    }
+}
+
+impl CellVisualisation for LifeLike {
+    fn new_cell(pos: &Pos2D) -> Self {
+        LifeLike::new_life(pos, LifeRule::CONWAY)
+    }
+
+    fn cell_to_rectangle(&self) -> CellRectangle {
+        let position = self.pos;
+        let color = if self.life { RGB::BLACK } else { RGB::WHITE };
+        CellRectangle { position, color }
+    }
+
+    /// Keeps the rule of the board.
+    fn clicked(&self) -> Self {
+        LifeLike::new_life(&self.pos, self.rule)
+    }
+}
+
+impl CellVisualisation for Generations {
+    fn new_cell(pos: &Pos2D) -> Self {
+        Generations::new_life(pos, GenerationsRule::BRIANS_BRAIN)
+    }
+
+    /// Dead cells are white and living ones black. The refractory states go through the colours
+    /// of the rainbow, starting over if there are more than seven of them.
+    fn cell_to_rectangle(&self) -> CellRectangle {
+        let position = self.pos;
+        let color = match self.state {
+            0 => RGB::WHITE,
+            1 => RGB::BLACK,
+            s => RGB::RAINBOW[(s as usize - 2) % RGB::RAINBOW.len()],
+        };
+        CellRectangle { position, color }
+    }
+
+    fn clicked(&self) -> Self {
+        Generations::new_life(&self.pos, self.rule)
+    }
+}
+
+impl CellVisualisation for LargerThanLife {
+    fn new_cell(pos: &Pos2D) -> Self {
+        LargerThanLife::new_life(pos, LtlRule::BUGS)
+    }
+
+    fn cell_to_rectangle(&self) -> CellRectangle {
+        let position = self.pos;
+        let color = if self.life { RGB::BLACK } else { RGB::WHITE };
+        CellRectangle { position, color }
+    }
+
+    fn clicked(&self) -> Self {
+        LargerThanLife::new_life(&self.pos, self.rule)
+    }
+}
+
+impl CellVisualisation for ForestFire {
+    fn new_cell(pos: &Pos2D) -> Self {
+        ForestFire::new_tree(pos)
+    }
+
+    fn cell_to_rectangle(&self) -> CellRectangle {
+        let position = self.pos;
+        let color = match self.tree {
+            Tree::Empty => RGB::WHITE,
+            Tree::Growing => RGB::GREEN,
+            Tree::Burning => RGB::RED,
+        };
+        CellRectangle { position, color }
+    }
+}
+
+impl CellVisualisation for Sand {
+    fn new_cell(pos: &Pos2D) -> Self {
+        Sand::new_grain(pos)
+    }
+
+    fn cell_to_rectangle(&self) -> CellRectangle {
+        let position = self.pos;
+        let color = if self.grain { RGB::ORANGE } else { RGB::WHITE };
+        CellRectangle { position, color }
+    }
+}
+
 impl CellVisualisation for GameOfLife {
     fn new_cell(pos: &Pos2D) -> Self {
         GameOfLife::new_life(pos)
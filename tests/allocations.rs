@@ -0,0 +1,105 @@
+//! Checks that computing generations doesn't allocate once the automaton is set up. The counting
+//! allocator replaces the global one, so it lives in its own test binary.
+use gailibrary::engine::automaton::{Automaton, AutomatonCell, Neighborhood};
+use gailibrary::examples::game_of_life::GameOfLife;
+use gailibrary::fields::{Dir2D, Pos2D};
+
+use spectral::prelude::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// Rule 90 on every row: the cell is on if exactly one of its left and right neighbours is on.
+/// Unlike the examples it reads the neighbours directly, so it doesn't allocate anything itself.
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Rule90 {
+    on: bool,
+    pos: Pos2D,
+}
+
+impl AutomatonCell for Rule90 {
+    fn update(&self, neighborhood: &dyn Neighborhood<Self>) -> Self {
+        let left = neighborhood.find_cell(&self.pos.move_by_one(Dir2D::Left));
+        let right = neighborhood.find_cell(&self.pos.move_by_one(Dir2D::Right));
+        Rule90 {
+            on: left.on != right.on,
+            pos: self.pos,
+        }
+    }
+
+    fn position(&self) -> Pos2D {
+        self.pos
+    }
+
+    fn new(pos: &Pos2D) -> Self {
+        Rule90 {
+            on: false,
+            pos: *pos,
+        }
+    }
+}
+
+#[test]
+fn should_not_allocate_in_steady_state() {
+    let mut auto = Automaton::<Rule90>::new(64, 16);
+    auto.add_change(&Rule90 {
+        on: true,
+        pos: Pos2D::new(32, 3),
+    });
+    auto.next();
+    auto.next();
+
+    let before = allocations();
+    for i in 0..20 {
+        auto.add_change(&Rule90 {
+            on: true,
+            pos: Pos2D::new(i, 5),
+        });
+        auto.next();
+    }
+    assert_that!(allocations() - before).is_equal_to(0);
+    assert_that!(auto.board_iter().filter(|(_, c)| c.on).count() > 0).is_true();
+}
+
+#[test]
+fn should_not_allocate_neighbourhoods() {
+    let mut auto = Automaton::<GameOfLife>::new(32, 32);
+    for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+        auto.add_change(&GameOfLife::new_life(&Pos2D::new(x, y)));
+    }
+    auto.next();
+    auto.next();
+
+    let before = allocations();
+    (0..20).for_each(|_| auto.next());
+    assert_that!(allocations() - before).is_equal_to(0);
+    assert_that!(auto.board_iter().filter(|(_, c)| c.life).count()).is_equal_to(5);
+}